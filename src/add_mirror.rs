@@ -0,0 +1,179 @@
+use emath::Pos2;
+use luexks_reassembly::{
+    shapes::shape_id::ShapeId,
+    utility::{angle::Angle, display_oriented_math::do3d_float_from},
+};
+
+use crate::{
+    restructure_vertices::restructure_vertices,
+    right_tri_angle_edge_case::rotate_right_tri_shroud_layer_mirror,
+    shape_container::ShapeContainer, shroud_layer_container::ShroudLayerContainer,
+};
+
+pub fn add_mirror(
+    shroud: &mut Vec<ShroudLayerContainer>,
+    index: usize,
+    loaded_shapes: &[ShapeContainer],
+    loaded_shapes_mirror_pairs: &[(usize, usize)],
+) {
+    let shroud_layer_mirror =
+        get_shroud_layer_mirror(shroud, index, loaded_shapes, loaded_shapes_mirror_pairs);
+    shroud[index].mirror_index_option = Some(shroud.len());
+    shroud.push(shroud_layer_mirror);
+}
+
+/// The layer `add_mirror` would add for the layer at `index`, already pointing back at it.
+pub fn get_shroud_layer_mirror(
+    shroud: &[ShroudLayerContainer],
+    index: usize,
+    loaded_shapes: &[ShapeContainer],
+    loaded_shapes_mirror_pairs: &[(usize, usize)],
+) -> ShroudLayerContainer {
+    let offset = shroud[index].shroud_layer.offset.clone().unwrap();
+    let (shape, shape_id, vertices) =
+        get_mirrored_shape_data(shroud, index, loaded_shapes, loaded_shapes_mirror_pairs);
+
+    let is_right_tri = shape_id == "RIGHT_TRI";
+
+    let mut shroud_layer_mirror = ShroudLayerContainer {
+        mirror_index_option: Some(index),
+        group_idx_option: None,
+        comments: Vec::new(),
+        shape_id,
+        vertices,
+        ..shroud[index].clone()
+    };
+
+    if shroud[index].invert_height_of_mirror {
+        *shroud_layer_mirror
+            .shroud_layer
+            .size
+            .as_mut()
+            .unwrap()
+            .y
+            .to_f32_mut() *= -1.0;
+    }
+
+    shroud_layer_mirror.shroud_layer.offset = Some(do3d_float_from(
+        offset.x.to_f32(),
+        -offset.y.to_f32(),
+        offset.z.to_f32(),
+    ));
+    shroud_layer_mirror.shroud_layer.angle = Some(Angle::Degree(
+        -shroud[index]
+            .shroud_layer
+            .angle
+            .clone()
+            .unwrap()
+            .as_degrees()
+            .get_value(),
+    ));
+    shroud_layer_mirror.shroud_layer.shape = Some(shape);
+
+    if is_right_tri {
+        rotate_right_tri_shroud_layer_mirror(&mut shroud_layer_mirror);
+    }
+
+    shroud_layer_mirror
+}
+
+/// Links every pair of unlinked layers where one is what `add_mirror` would make of the other.
+/// Layers that are their own mirror, like ones centred on the x axis, are left alone.
+pub fn link_shroud_layer_mirrors(
+    shroud: &mut [ShroudLayerContainer],
+    loaded_shapes: &[ShapeContainer],
+    loaded_shapes_mirror_pairs: &[(usize, usize)],
+) {
+    for index in 0..shroud.len() {
+        if shroud[index].mirror_index_option.is_some() {
+            continue;
+        }
+        let expected_mirror =
+            get_shroud_layer_mirror(shroud, index, loaded_shapes, loaded_shapes_mirror_pairs);
+        if is_shroud_layer_mirror(&expected_mirror, &shroud[index]) {
+            continue;
+        }
+        if let Some(mirror_index) = (index + 1..shroud.len()).find(|mirror_index| {
+            shroud[*mirror_index].mirror_index_option.is_none()
+                && is_shroud_layer_mirror(&expected_mirror, &shroud[*mirror_index])
+        }) {
+            shroud[index].mirror_index_option = Some(mirror_index);
+            shroud[mirror_index].mirror_index_option = Some(index);
+        }
+    }
+}
+
+const MIRROR_TOLERANCE: f32 = 0.001;
+
+/// Heights are compared by size alone for shapes that are symmetric about the x axis, since a
+/// negative height looks the same on them.
+fn is_shroud_layer_mirror(
+    expected_mirror: &ShroudLayerContainer,
+    shroud_layer_container: &ShroudLayerContainer,
+) -> bool {
+    let expected = &expected_mirror.shroud_layer;
+    let actual = &shroud_layer_container.shroud_layer;
+    let (expected_offset, actual_offset) = (expected.offset.as_ref(), actual.offset.as_ref());
+    let (expected_size, actual_size) = (expected.size.as_ref(), actual.size.as_ref());
+    let (Some(expected_offset), Some(actual_offset), Some(expected_size), Some(actual_size)) =
+        (expected_offset, actual_offset, expected_size, actual_size)
+    else {
+        return false;
+    };
+    let is_near = |a: f32, b: f32| (a - b).abs() < MIRROR_TOLERANCE;
+    let angle_difference = expected.angle.clone().unwrap().as_degrees().get_value()
+        - actual.angle.clone().unwrap().as_degrees().get_value();
+    expected.shape == actual.shape
+        && expected.color_1 == actual.color_1
+        && expected.color_2 == actual.color_2
+        && expected.line_color == actual.line_color
+        && is_near(expected.taper.unwrap_or(1.0), actual.taper.unwrap_or(1.0))
+        && is_near(expected_offset.x.to_f32(), actual_offset.x.to_f32())
+        && is_near(expected_offset.y.to_f32(), actual_offset.y.to_f32())
+        && is_near(expected_offset.z.to_f32(), actual_offset.z.to_f32())
+        && is_near(expected_size.x.to_f32(), actual_size.x.to_f32())
+        && (is_near(expected_size.y.to_f32(), actual_size.y.to_f32())
+            || (!expected_mirror.invert_height_of_mirror
+                && is_near(expected_size.y.to_f32(), -actual_size.y.to_f32())))
+        && is_near((angle_difference + 180.0).rem_euclid(360.0), 180.0)
+}
+
+pub fn get_mirrored_shape_data(
+    shroud: &[ShroudLayerContainer],
+    index: usize,
+    loaded_shapes: &[ShapeContainer],
+    loaded_shapes_mirror_pairs: &[(usize, usize)],
+) -> (ShapeId, String, Vec<Pos2>) {
+    let shape = shroud[index].shroud_layer.shape.clone().unwrap();
+    let shape_id = shroud[index].shape_id.clone();
+    let vertices = shroud[index].vertices.clone();
+    let Some(loaded_shape_index) = loaded_shapes
+        .iter()
+        .position(|loaded_shape| loaded_shape.s.get_id().unwrap() == shape)
+    else {
+        return (shape, shape_id, vertices);
+    };
+
+    let loaded_shape_mirror_index_l_option = loaded_shapes_mirror_pairs
+        .iter()
+        .position(|(l, _)| *l == loaded_shape_index);
+    let (shape, shape_id, vertices) = if let Some(index) = loaded_shape_mirror_index_l_option {
+        let r = loaded_shapes_mirror_pairs[index].1;
+        let shape = loaded_shapes[r].s.get_id().unwrap();
+        let vertices = restructure_vertices(loaded_shapes[r].s.get_first_scale_vertices());
+        (shape.clone(), shape.to_string(), vertices)
+    } else {
+        let loaded_shape_mirror_index_r_option = loaded_shapes_mirror_pairs
+            .iter()
+            .position(|(_, r)| *r == loaded_shape_index);
+        if let Some(index) = loaded_shape_mirror_index_r_option {
+            let l = loaded_shapes_mirror_pairs[index].0;
+            let shape = loaded_shapes[l].s.get_id().unwrap();
+            let vertices = restructure_vertices(loaded_shapes[l].s.get_first_scale_vertices());
+            (shape.clone(), shape.to_string(), vertices)
+        } else {
+            (shape, shape_id, vertices)
+        }
+    };
+    (shape, shape_id, vertices)
+}
@@ -6,7 +6,10 @@ use luexks_reassembly::{
 };
 
 use crate::{
-    DEFAULT_SQUARE, color_type_conversion::rgba_to_color,
+    DEFAULT_SQUARE,
+    color_type_conversion::{rgba_to_color, rgba_to_color_string},
+    restructure_vertices::restructure_vertices,
+    shape_container::ShapeContainer,
     verts_to_convex_hull::verts_to_convex_hull,
 };
 
@@ -107,6 +110,38 @@ impl BlockContainer {
         }
     }
 
+    pub fn set_shape(&mut self, shape: &ShapeContainer, scale: usize) {
+        self.max_scale = shape.s.get_scale_count();
+        let scale = scale.clamp(1, self.max_scale);
+        self.block.shape = shape.s.get_id();
+        self.block.scale = Some(scale);
+        self.shape_id = shape.s.get_id().unwrap().to_string();
+        self.vertices = restructure_vertices(shape.s.get_nth_scale_vertices(scale - 1));
+    }
+
+    pub fn set_color(&mut self, color_id: ShroudLayerColor, rgba: Rgba) {
+        let (color, input_color, block_color) = match color_id {
+            ShroudLayerColor::Color1 => (
+                &mut self.color_1,
+                &mut self.input_color_1,
+                &mut self.block.color_1,
+            ),
+            ShroudLayerColor::Color2 => (
+                &mut self.color_2,
+                &mut self.input_color_2,
+                &mut self.block.color_2,
+            ),
+            ShroudLayerColor::LineColor => (
+                &mut self.line_color,
+                &mut self.input_line_color,
+                &mut self.block.line_color,
+            ),
+        };
+        *color = rgba;
+        *input_color = rgba_to_color_string(rgba);
+        *block_color = Some(rgba_to_color(rgba));
+    }
+
     pub fn update_non_turreted_offset(&mut self) {
         let avg_vert_pos = match &*self.shape_id {
             "COMMAND" | "CANNON" | "CANNON2" | "MISSILE_LAUNCHER" | "MISSILE_SHORT"
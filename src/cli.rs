@@ -0,0 +1,299 @@
+use std::{
+    fmt::Write as _,
+    fs::{read_to_string, write},
+    io::{Read, stdin},
+};
+
+use emath::Pos2;
+use itertools::Itertools;
+use parse_vanilla_shapes::get_vanilla_shapes;
+
+use crate::{
+    add_mirror::link_shroud_layer_mirrors,
+    export::export_shroud,
+    export_format::ExportFormat,
+    json_interchange::{export_json, import_json, is_json_text},
+    mirror_pairs::get_loaded_shapes_mirror_pairs,
+    parse_shapes_text::parse_shapes_text,
+    parse_shroud_text::parse_shroud_text_lenient,
+    parsing::evaluate_number_expression,
+    project_format::{
+        ProjectSections, format_table, is_project_text, parse_project, parse_project_shapes,
+        parse_project_shroud, project_header, table_pos2, to_long_string, write_project_shroud,
+    },
+    shape_container::{restructure_shapes, with_imported_shapes},
+};
+
+const USAGE: &str = "\
+Usage: luexks_shroud_editor <COMMAND> [OPTIONS] [INPUT]
+
+Commands:
+  validate   Check that INPUT parses, printing every problem and exiting with 1 if there are any
+  normalise  Rewrite INPUT the way the editor exports it
+  convert    Convert INPUT between a shroud .lua file and a .shroudproj project file
+
+INPUT is a shroud={...} .lua file, a .shroudproj project file or a .json file. Leave it out or use - for stdin.
+
+Options:
+  --shapes <FILE>         Load custom shapes from a shapes.lua file before parsing
+  --offset <X,Y>          Move every shroud layer by X,Y
+  --to <shroud|project|json>
+                          Output format, defaults to the input format, or for convert to project
+                          from a shroud and to a shroud otherwise
+  -o, --output <FILE>     Write to FILE instead of stdout
+  -h, --help              Print this";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Command {
+    Validate,
+    Normalise,
+    Convert,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Shroud,
+    Project,
+    Json,
+}
+
+struct Options {
+    command: Command,
+    input_option: Option<String>,
+    output_option: Option<String>,
+    shapes_option: Option<String>,
+    offset_option: Option<(f32, f32)>,
+    format_option: Option<Format>,
+}
+
+/// Returns `None` if the arguments are not a CLI command, in which case the GUI should start.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = match args.first()?.as_str() {
+        "validate" => Command::Validate,
+        "normalise" | "normalize" => Command::Normalise,
+        "convert" => Command::Convert,
+        "-h" | "--help" | "help" => {
+            attach_parent_console();
+            println!("{USAGE}");
+            return Some(0);
+        }
+        _ => return None,
+    };
+    attach_parent_console();
+    Some(
+        match parse_options(command, &args[1..]).and_then(|options| execute(&options)) {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("{err}");
+                1
+            }
+        },
+    )
+}
+
+/// Release builds on Windows are GUI programs with no console of their own, so output would
+/// otherwise go nowhere when run from a terminal.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails harmlessly when there is no parent console or one is already attached.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+fn parse_options(command: Command, args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        command,
+        input_option: None,
+        output_option: None,
+        shapes_option: None,
+        offset_option: None,
+        format_option: None,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or(format!("Missing value for {arg}\n\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--shapes" => options.shapes_option = Some(value()?),
+            "-o" | "--output" => options.output_option = Some(value()?),
+            "--offset" => {
+                let offset = value()?;
+                options.offset_option = Some(
+                    offset
+                        .split_once(',')
+                        .and_then(|(x, y)| {
+                            Some((
+                                evaluate_number_expression(x)?,
+                                evaluate_number_expression(y)?,
+                            ))
+                        })
+                        .ok_or(format!("Failed to parse offset: `{offset}` :("))?,
+                );
+            }
+            "--to" => {
+                options.format_option = Some(match value()?.as_str() {
+                    "shroud" | "lua" => Format::Shroud,
+                    "project" | "shroudproj" => Format::Project,
+                    "json" => Format::Json,
+                    format => return Err(format!("Unknown format: `{format}` :(")),
+                })
+            }
+            _ if options.input_option.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+                options.input_option = Some(arg.clone())
+            }
+            _ => return Err(format!("Unexpected argument: `{arg}`\n\n{USAGE}")),
+        }
+    }
+    Ok(options)
+}
+
+fn execute(options: &Options) -> Result<(), String> {
+    let input = match options.input_option.as_deref() {
+        None | Some("-") => {
+            let mut s = String::new();
+            stdin()
+                .read_to_string(&mut s)
+                .map_err(|_| "Could not read stdin :(".to_string())?;
+            s
+        }
+        Some(path) => read_to_string(path).map_err(|_| format!("Could not open {path} :("))?,
+    };
+
+    let vanilla_shapes = restructure_shapes(get_vanilla_shapes());
+    let mut loaded_shapes = vanilla_shapes.clone();
+    let mut loaded_shapes_mirror_pairs = Vec::new();
+    let mut shapes_text = String::new();
+    if let Some(shapes_path) = &options.shapes_option {
+        shapes_text =
+            read_to_string(shapes_path).map_err(|_| format!("Could not open {shapes_path} :("))?;
+        let (imported_shapes, mirror_pairs, non_mirrors) =
+            parse_shapes_text(&shapes_text).map_err(|err| err.to_string())?;
+        loaded_shapes = with_imported_shapes(&vanilla_shapes, imported_shapes, &non_mirrors);
+        loaded_shapes_mirror_pairs = mirror_pairs;
+    }
+
+    // Shroud and JSON inputs are kept relative to a block at the origin, and project inputs keep
+    // their own block.
+    let mut block_offset = Pos2::default();
+    let mut tables_option = None;
+    let (input_format, mut shroud, groups, trailing_comments) = if is_project_text(&input) {
+        let sections = parse_project(&input).map_err(|err| err.to_string())?;
+        let (imported_shapes, _, non_mirrors) =
+            parse_project_shapes(&sections).map_err(|err| err.to_string())?;
+        loaded_shapes = with_imported_shapes(&vanilla_shapes, imported_shapes, &non_mirrors);
+        shapes_text = sections.shapes.to_string();
+        let (shroud, trailing_comments) =
+            parse_project_shroud(&sections, &loaded_shapes).map_err(|err| err.to_string())?;
+        block_offset = table_pos2(&sections.block, "offset").unwrap_or_default();
+        tables_option = Some(project_tables(&sections));
+        (Format::Project, shroud, sections.groups, trailing_comments)
+    } else if is_json_text(&input) {
+        let (shroud, groups, trailing_comments) =
+            import_json(&input, &loaded_shapes, block_offset).map_err(|err| err.to_string())?;
+        (Format::Json, shroud, groups, trailing_comments)
+    } else {
+        let (mut shroud, trailing_comments, problems) =
+            parse_shroud_text_lenient(&input, &loaded_shapes);
+        if !problems.is_empty() {
+            return Err(problems
+                .iter()
+                .map(|problem| problem.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"));
+        }
+        loaded_shapes_mirror_pairs.extend(get_loaded_shapes_mirror_pairs(&loaded_shapes));
+        link_shroud_layer_mirrors(&mut shroud, &loaded_shapes, &loaded_shapes_mirror_pairs);
+        (Format::Shroud, shroud, Vec::new(), trailing_comments)
+    };
+    if options.command == Command::Validate {
+        let missing_shape_ids = shroud
+            .iter()
+            .filter(|shroud_layer| shroud_layer.shape_is_missing)
+            .map(|shroud_layer| shroud_layer.shape_id.clone())
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+        if !missing_shape_ids.is_empty() {
+            return Err(format!(
+                "Shapes not loaded: {} :(",
+                missing_shape_ids.join(", ")
+            ));
+        }
+        return Ok(());
+    }
+
+    if let Some((x, y)) = options.offset_option {
+        shroud.iter_mut().for_each(|shroud_layer| {
+            let offset = shroud_layer.shroud_layer.offset.as_mut().unwrap();
+            *offset.x.to_f32_mut() += x;
+            *offset.y.to_f32_mut() += y;
+        });
+    }
+
+    let output_format = options
+        .format_option
+        .unwrap_or(match (options.command, input_format) {
+            (Command::Convert, Format::Shroud) => Format::Project,
+            (Command::Convert, Format::Project | Format::Json) => Format::Shroud,
+            _ => input_format,
+        });
+    let output = match output_format {
+        Format::Shroud => export_shroud(
+            &shroud,
+            block_offset,
+            &trailing_comments,
+            &ExportFormat::default(),
+        ),
+        Format::Project => {
+            let (editor, block, reference_image, reference_image_path_option) = tables_option
+                .unwrap_or_else(|| {
+                    (
+                        "{ }".to_string(),
+                        "{ use_non_turreted_offset=false offset={0,0} }".to_string(),
+                        "{ }".to_string(),
+                        None,
+                    )
+                });
+            let mut s = project_header();
+            writeln!(s, "editor={editor}").unwrap();
+            writeln!(s, "block={block}").unwrap();
+            writeln!(s, "reference_image={reference_image}").unwrap();
+            if let Some(reference_image_path) = reference_image_path_option {
+                writeln!(s, "reference_image_path={reference_image_path}").unwrap();
+            }
+            write_project_shroud(&mut s, &shapes_text, &shroud, &groups, &trailing_comments);
+            s
+        }
+        Format::Json => export_json(&shroud, &groups, &trailing_comments, block_offset),
+    };
+    match &options.output_option {
+        Some(path) => write(path, output).map_err(|_| format!("Could not write {path} :(")),
+        None => {
+            println!("{output}");
+            Ok(())
+        }
+    }
+}
+
+/// A project's editor, block and reference image tables, and its reference image path, written out
+/// again as they were.
+fn project_tables(sections: &ProjectSections) -> (String, String, String, Option<String>) {
+    (
+        format_table(&sections.editor),
+        format_table(&sections.block),
+        format_table(&sections.reference_image),
+        sections.reference_image_path_option.map(to_long_string),
+    )
+}
@@ -1,4 +1,4 @@
-use egui::Rgba;
+use ecolor::Rgba;
 use luexks_reassembly::utility::color::Color;
 
 pub fn rgba_to_color(rgba: Rgba) -> Color {
@@ -0,0 +1,48 @@
+use std::{
+    env,
+    fs::{self, create_dir_all},
+    io,
+    path::{Path, PathBuf},
+};
+
+const CONFIG_DIR_NAME: &str = "luexks_shroud_editor";
+
+/// `$XDG_CONFIG_HOME` or `~/.config` on Linux, `%APPDATA%` on Windows and `~/Library/Application Support` on macOS.
+pub fn config_dir() -> Option<PathBuf> {
+    let base_dir_option = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base_dir_option.map(|base_dir| base_dir.join(CONFIG_DIR_NAME))
+}
+
+/// Falls back to the working directory, where everything used to be kept, if there is no home directory.
+pub fn config_file_path(file_name: &str) -> PathBuf {
+    config_dir()
+        .map(|dir| dir.join(file_name))
+        .unwrap_or_else(|| PathBuf::from(file_name))
+}
+
+pub fn write_config_file(file_name: &str, contents: &str) -> io::Result<()> {
+    if let Some(dir) = config_dir() {
+        create_dir_all(dir)?;
+    }
+    fs::write(config_file_path(file_name), contents)
+}
+
+/// Copies a file from the working directory into the config directory, unless the config directory already has one.
+pub fn migrate_legacy_config_file(legacy_path: &str, file_name: &str) {
+    let path = config_file_path(file_name);
+    if path.exists() || !Path::new(legacy_path).exists() {
+        return;
+    }
+    if let Ok(contents) = fs::read_to_string(legacy_path) {
+        let _ = write_config_file(file_name, &contents);
+    }
+}
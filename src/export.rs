@@ -0,0 +1,273 @@
+use std::fmt::Write as _;
+
+use emath::Pos2;
+use luexks_reassembly::{blocks::shroud_layer::ShroudLayerColor, shapes::shape::Shape};
+use parse_vanilla_shapes::VANILLA_SHAPE_COUNT;
+
+use crate::{
+    export_format::{ExportFormat, ExportLayout, ShroudLayerField},
+    shape_container::ShapeContainer,
+    shroud_layer_container::ShroudLayerContainer,
+};
+
+/// Formats the shroud as it would go in a block, moving each layer by `-block_offset` and leaving
+/// out default angles and tapers. Comments go on their own lines before their layer, and
+/// `trailing_comments` go before the closing brace.
+pub fn export_shroud(
+    shroud: &[ShroudLayerContainer],
+    block_offset: Pos2,
+    trailing_comments: &[String],
+    export_format: &ExportFormat,
+) -> String {
+    let mut s = String::from("shroud={");
+    match export_format.layout {
+        ExportLayout::OneLayerPerLine => {
+            s.push('\n');
+            shroud.iter().for_each(|shroud_layer_container| {
+                shroud_layer_container
+                    .comments
+                    .iter()
+                    .for_each(|comment| writeln!(s, "{comment}").unwrap());
+                let formatted_shroud_layer =
+                    format_shroud_layer(shroud_layer_container, block_offset, export_format);
+                writeln!(s, "{}{formatted_shroud_layer}", export_format.indentation).unwrap();
+            });
+            trailing_comments
+                .iter()
+                .for_each(|comment| writeln!(s, "{comment}").unwrap());
+        }
+        ExportLayout::Compact => {
+            shroud
+                .iter()
+                .enumerate()
+                .for_each(|(idx, shroud_layer_container)| {
+                    if idx > 0 {
+                        s.push(',');
+                    }
+                    shroud_layer_container
+                        .comments
+                        .iter()
+                        .for_each(|comment| write!(s, "\n{comment}\n").unwrap());
+                    s.push_str(&format_shroud_layer(
+                        shroud_layer_container,
+                        block_offset,
+                        export_format,
+                    ));
+                });
+            if !trailing_comments.is_empty() {
+                trailing_comments
+                    .iter()
+                    .for_each(|comment| write!(s, "\n{comment}").unwrap());
+                s.push('\n');
+            }
+        }
+    }
+    s.push('}');
+    s
+}
+
+/// Fields the editor did not understand go last, as they were written.
+fn format_shroud_layer(
+    shroud_layer_container: &ShroudLayerContainer,
+    block_offset: Pos2,
+    export_format: &ExportFormat,
+) -> String {
+    let shroud_layer = &shroud_layer_container.shroud_layer;
+    let omit_defaults = export_format.omit_defaults;
+    let number = |number: f32| export_format.format_number(number);
+    let color_id = |color_option: Option<ShroudLayerColor>, default_color: ShroudLayerColor| {
+        color_option
+            .filter(|color| !(omit_defaults && *color == default_color))
+            .map(|color| shroud_layer_color_id(&color).to_string())
+    };
+    let fields = export_format
+        .field_order
+        .iter()
+        .filter_map(|field| {
+            let value = match field {
+                ShroudLayerField::Shape => shroud_layer
+                    .shape
+                    .as_ref()
+                    .filter(|_| !(omit_defaults && shroud_layer_container.shape_id == "SQUARE"))?
+                    .to_string(),
+                ShroudLayerField::Size => {
+                    let size = shroud_layer.size.as_ref()?;
+                    format!(
+                        "{{{},{}}}",
+                        number(size.x.to_f32()),
+                        number(size.y.to_f32())
+                    )
+                }
+                ShroudLayerField::Offset => {
+                    let offset = shroud_layer.offset.as_ref()?;
+                    let offset = [
+                        offset.x.to_f32() - block_offset.x,
+                        offset.y.to_f32() - block_offset.y,
+                        offset.z.to_f32(),
+                    ]
+                    .map(number);
+                    if omit_defaults && offset.iter().all(|component| component == "0") {
+                        return None;
+                    }
+                    format!("{{{}}}", offset.join(","))
+                }
+                ShroudLayerField::TriColorId => {
+                    color_id(shroud_layer.color_1, ShroudLayerColor::Color1)?
+                }
+                ShroudLayerField::TriColor1Id => {
+                    color_id(shroud_layer.color_2, ShroudLayerColor::Color2)?
+                }
+                ShroudLayerField::LineColorId => {
+                    color_id(shroud_layer.line_color, ShroudLayerColor::LineColor)?
+                }
+                ShroudLayerField::Angle => {
+                    let angle = shroud_layer.angle.as_ref()?.as_radians().get_value();
+                    if angle.abs() < f32::EPSILON {
+                        return None;
+                    }
+                    export_format.format_angle(angle)
+                }
+                ShroudLayerField::Taper => {
+                    let taper = shroud_layer.taper?;
+                    if shroud_layer_container.shape_id != "SQUARE" || taper == 1.0 {
+                        return None;
+                    }
+                    number(taper)
+                }
+            };
+            Some(format!("{}={value}", field.name()))
+        })
+        .chain(
+            shroud_layer_container
+                .unknown_fields
+                .iter()
+                .map(|(name, value)| format!("{name}={value}")),
+        )
+        .collect::<Vec<_>>();
+    let separator = match export_format.layout {
+        ExportLayout::OneLayerPerLine => ", ",
+        ExportLayout::Compact => ",",
+    };
+    format!("{{{}}}", fields.join(separator))
+}
+
+pub fn shroud_layer_color_id(shroud_layer_color: &ShroudLayerColor) -> u8 {
+    match shroud_layer_color {
+        ShroudLayerColor::Color1 => 0,
+        ShroudLayerColor::Color2 => 1,
+        ShroudLayerColor::LineColor => 2,
+    }
+}
+
+/// Mirrored custom shapes are stored already flipped, so they are written back as `mirror_of`
+/// entries using the `(mirror, mirror_of)` pairs recorded by `parse_shapes_text`. Every entry and
+/// field is followed by a comma except the last in its table, as Lua wants.
+pub fn export_shapes(
+    loaded_shapes: &[ShapeContainer],
+    loaded_shapes_mirror_pairs: &[(usize, usize)],
+) -> String {
+    let shapes = loaded_shapes
+        .iter()
+        .enumerate()
+        .skip(VANILLA_SHAPE_COUNT)
+        .filter_map(|(shape_idx, shape)| {
+            let id = shape.s.get_id().unwrap();
+            if let Some((_, mirror_of_idx)) = loaded_shapes_mirror_pairs
+                .iter()
+                .find(|(mirror_idx, _)| *mirror_idx == shape_idx)
+            {
+                let mirror_of = loaded_shapes[*mirror_of_idx].s.get_id().unwrap();
+                return Some(format!("\t{{{id}, {{}}, mirror_of={mirror_of}}}"));
+            }
+            let Shape::Standard { scales, .. } = &shape.s else {
+                return None;
+            };
+            let scales = scales
+                .iter()
+                .enumerate()
+                .map(|(scale_idx, scale)| {
+                    let verts = scale
+                        .verts
+                        .0
+                        .iter()
+                        .map(|vert| format!("{{{},{}}}", vert.0.x.to_f32(), vert.0.y.to_f32()))
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    let mut fields = vec![format!("verts={{{verts}}}")];
+                    if let Some(Some(ports)) = shape.scale_ports.get(scale_idx) {
+                        fields.push(format!("ports={ports}"));
+                    }
+                    format!("\t\t\t{{\n\t\t\t\t{}\n\t\t\t}}", fields.join(",\n\t\t\t\t"))
+                })
+                .collect::<Vec<_>>()
+                .join(",\n");
+            Some(format!("\t{{{id},\n\t\t{{\n{scales}\n\t\t}}\n\t}}"))
+        })
+        .collect::<Vec<_>>();
+    if shapes.is_empty() {
+        String::from("{\n}")
+    } else {
+        format!("{{\n{}\n}}", shapes.join(",\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_shapes_text::parse_shapes_text;
+
+    const SHAPES_TEXT: &str = "{
+	{200,
+		{
+			{
+				verts={{0,0},{10,0},{0,10}},
+				ports={{0,0.5},{1,0.5}}
+			},
+			{
+				verts={{0,0},{20,0},{0,20}}
+			}
+		}
+	},
+	{201, {}, mirror_of=200},
+	{202,
+		{
+			{
+				verts={{-5,-5},{-5,5},{5,5},{5,-5}}
+			}
+		}
+	}
+}";
+
+    /// What the editor has loaded after importing `text`, with stand-ins for the vanilla shapes.
+    fn loaded_shapes(text: &str) -> (Vec<ShapeContainer>, Vec<(usize, usize)>) {
+        let (shapes, mirror_pairs, _) = parse_shapes_text(text).unwrap();
+        let loaded_shapes = vec![shapes[0].clone(); VANILLA_SHAPE_COUNT]
+            .into_iter()
+            .chain(shapes)
+            .collect();
+        (loaded_shapes, mirror_pairs)
+    }
+
+    #[test]
+    fn export_shapes_round_trips() {
+        let (loaded_shapes, mirror_pairs) = loaded_shapes(SHAPES_TEXT);
+        let exported = export_shapes(&loaded_shapes, &mirror_pairs);
+        assert_eq!(exported, SHAPES_TEXT);
+        let (reloaded_shapes, reloaded_mirror_pairs) = loaded_shapes(&exported);
+        assert_eq!(reloaded_mirror_pairs, mirror_pairs);
+        assert_eq!(reloaded_shapes.len(), loaded_shapes.len());
+        assert_eq!(
+            reloaded_shapes[VANILLA_SHAPE_COUNT].scale_ports,
+            vec![Some("{{0,0.5},{1,0.5}}".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn export_no_shapes() {
+        let (loaded_shapes, _) = loaded_shapes(SHAPES_TEXT);
+        assert_eq!(
+            export_shapes(&loaded_shapes[..VANILLA_SHAPE_COUNT], &[]),
+            "{\n}"
+        );
+    }
+}
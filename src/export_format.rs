@@ -0,0 +1,231 @@
+use std::fmt::Display;
+
+/// The fields of a shroud layer, by the names the game reads them as.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShroudLayerField {
+    Shape,
+    Size,
+    Offset,
+    TriColorId,
+    TriColor1Id,
+    LineColorId,
+    Angle,
+    Taper,
+}
+
+impl ShroudLayerField {
+    pub const ALL: [ShroudLayerField; 8] = [
+        ShroudLayerField::Shape,
+        ShroudLayerField::Size,
+        ShroudLayerField::Offset,
+        ShroudLayerField::TriColorId,
+        ShroudLayerField::TriColor1Id,
+        ShroudLayerField::LineColorId,
+        ShroudLayerField::Angle,
+        ShroudLayerField::Taper,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShroudLayerField::Shape => "shape",
+            ShroudLayerField::Size => "size",
+            ShroudLayerField::Offset => "offset",
+            ShroudLayerField::TriColorId => "tri_color_id",
+            ShroudLayerField::TriColor1Id => "tri_color1_id",
+            ShroudLayerField::LineColorId => "line_color_id",
+            ShroudLayerField::Angle => "angle",
+            ShroudLayerField::Taper => "taper",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ShroudLayerField> {
+        ShroudLayerField::ALL
+            .into_iter()
+            .find(|field| field.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportLayout {
+    OneLayerPerLine,
+    /// The whole shroud on one line, apart from comments, which always get their own.
+    Compact,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AngleFormat {
+    Radians,
+    /// Like `45*pi/180`, which is easier to read and still evaluates to radians.
+    DegreesExpression,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Indentation {
+    Tabs,
+    Spaces(usize),
+}
+
+impl Display for Indentation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Indentation::Tabs => write!(f, "\t"),
+            Indentation::Spaces(count) => write!(f, "{}", " ".repeat(*count)),
+        }
+    }
+}
+
+/// How `export_shroud` writes a shroud.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportFormat {
+    /// Numbers are rounded to this many decimal places, and trailing zeros are dropped.
+    pub decimal_places_option: Option<usize>,
+    pub layout: ExportLayout,
+    pub field_order: [ShroudLayerField; 8],
+    pub angle_format: AngleFormat,
+    /// Leaves out shapes, offsets and colours that are what the game uses when they are missing.
+    /// Zero angles and tapers of 1 are always left out.
+    pub omit_defaults: bool,
+    pub indentation: Indentation,
+}
+
+impl Default for ExportFormat {
+    fn default() -> Self {
+        Self {
+            decimal_places_option: None,
+            layout: ExportLayout::OneLayerPerLine,
+            field_order: ShroudLayerField::ALL,
+            angle_format: AngleFormat::Radians,
+            omit_defaults: false,
+            indentation: Indentation::Tabs,
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn format_number(&self, number: f32) -> String {
+        let Some(decimal_places) = self.decimal_places_option else {
+            return number.to_string();
+        };
+        let s = format!("{number:.decimal_places$}");
+        let s = if s.contains('.') {
+            s.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &s
+        };
+        if s == "-0" {
+            "0".to_string()
+        } else {
+            s.to_string()
+        }
+    }
+
+    pub fn format_angle(&self, radians: f32) -> String {
+        match self.angle_format {
+            AngleFormat::Radians => self.format_number(radians),
+            AngleFormat::DegreesExpression => {
+                format!("{}*pi/180", self.format_number(radians.to_degrees()))
+            }
+        }
+    }
+
+    /// The settings as `key=value` pairs, as kept in the preferences and export presets files.
+    pub fn key_values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "decimal_places",
+                self.decimal_places_option
+                    .map_or("none".to_string(), |decimal_places| {
+                        decimal_places.to_string()
+                    }),
+            ),
+            (
+                "layout",
+                match self.layout {
+                    ExportLayout::OneLayerPerLine => "one_layer_per_line",
+                    ExportLayout::Compact => "compact",
+                }
+                .to_string(),
+            ),
+            (
+                "field_order",
+                self.field_order
+                    .iter()
+                    .map(ShroudLayerField::name)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            (
+                "angle",
+                match self.angle_format {
+                    AngleFormat::Radians => "radians",
+                    AngleFormat::DegreesExpression => "degrees",
+                }
+                .to_string(),
+            ),
+            ("omit_defaults", self.omit_defaults.to_string()),
+            (
+                "indentation",
+                match self.indentation {
+                    Indentation::Tabs => "tabs".to_string(),
+                    Indentation::Spaces(count) => count.to_string(),
+                },
+            ),
+        ]
+    }
+
+    /// Returns whether `key` was known and `value` could be read. Nothing is changed if not.
+    pub fn set_key_value(&mut self, key: &str, value: &str) -> bool {
+        match key {
+            "decimal_places" => {
+                if value == "none" {
+                    self.decimal_places_option = None;
+                } else if let Ok(decimal_places) = value.parse() {
+                    self.decimal_places_option = Some(decimal_places);
+                } else {
+                    return false;
+                }
+            }
+            "layout" => match value {
+                "one_layer_per_line" => self.layout = ExportLayout::OneLayerPerLine,
+                "compact" => self.layout = ExportLayout::Compact,
+                _ => return false,
+            },
+            "field_order" => {
+                let field_order = value
+                    .split(',')
+                    .map(|name| ShroudLayerField::from_name(name.trim()))
+                    .collect::<Option<Vec<_>>>();
+                match field_order.and_then(|field_order| <[_; 8]>::try_from(field_order).ok()) {
+                    Some(field_order)
+                        if ShroudLayerField::ALL
+                            .iter()
+                            .all(|field| field_order.contains(field)) =>
+                    {
+                        self.field_order = field_order;
+                    }
+                    _ => return false,
+                }
+            }
+            "angle" => match value {
+                "radians" => self.angle_format = AngleFormat::Radians,
+                "degrees" => self.angle_format = AngleFormat::DegreesExpression,
+                _ => return false,
+            },
+            "omit_defaults" => match value.parse() {
+                Ok(omit_defaults) => self.omit_defaults = omit_defaults,
+                Err(_) => return false,
+            },
+            "indentation" => {
+                if value == "tabs" {
+                    self.indentation = Indentation::Tabs;
+                } else if let Ok(count) = value.parse() {
+                    self.indentation = Indentation::Spaces(count);
+                } else {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
@@ -1,7 +1,13 @@
+use std::{
+    fs::{remove_file, rename, write},
+    io,
+    path::Path,
+};
+
 use egui::Context;
 use egui_file_dialog::DialogState;
 
-use crate::shroud_editor::ShroudEditor;
+use crate::shroud_editor::{ShroudEditor, live_link::LiveLinkTarget};
 
 pub enum WhichFileDialog {
     ReferenceImage,
@@ -9,6 +15,13 @@ pub enum WhichFileDialog {
     ShapeImport,
     ShroudExport,
     ShapeExport,
+    ProjectSave,
+    ProjectOpen,
+    BlocksOpen,
+    ModFolderOpen,
+    LiveLinkShroudFile,
+    JsonImport,
+    JsonExport,
 }
 
 impl ShroudEditor {
@@ -19,20 +32,67 @@ impl ShroudEditor {
                 WhichFileDialog::ReferenceImage => {
                     self.import_reference_image_from_file(ctx, path);
                 }
+                WhichFileDialog::ShroudImport => {
+                    self.import_shroud_from_file(path);
+                }
                 WhichFileDialog::ShroudExport => {
                     self.export_shroud_to_file(path);
                 }
                 WhichFileDialog::ShapeImport => {
                     self.import_shapes_from_file(path);
                 }
-                _ => {}
+                WhichFileDialog::ShapeExport => {
+                    self.export_shapes_to_file(path);
+                }
+                WhichFileDialog::ProjectSave => {
+                    self.save_project_to_file(path);
+                }
+                WhichFileDialog::ProjectOpen => {
+                    self.open_project_from_file(ctx, path);
+                }
+                WhichFileDialog::BlocksOpen => {
+                    self.open_blocks_file(path);
+                }
+                WhichFileDialog::ModFolderOpen => {
+                    self.open_mod_folder(path);
+                }
+                WhichFileDialog::LiveLinkShroudFile => {
+                    self.set_live_link_target(LiveLinkTarget::ShroudFile(path));
+                }
+                WhichFileDialog::JsonImport => {
+                    self.import_json_from_file(path);
+                }
+                WhichFileDialog::JsonExport => {
+                    self.export_json_to_file(path);
+                }
             }
         }
     }
 
+    pub fn save_file_dialog(
+        &mut self,
+        which_file_dialog: WhichFileDialog,
+        default_file_name: &str,
+    ) {
+        self.which_file_import = which_file_dialog;
+        self.file_dialog.config_mut().default_file_name = default_file_name.to_string();
+        self.file_dialog.save_file();
+    }
+
     pub fn file_dialog_visual_panel_key_bindings_enabled_logic(&mut self) {
         if matches!(*self.file_dialog.state(), DialogState::Open) {
             self.visual_panel_key_bindings_enabled = false;
         }
     }
 }
+
+/// Writes next to `path` and then renames over it, so nothing reading `path` sees half a file.
+pub fn write_file_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let mut temp_file_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_file_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_file_name);
+    write(&temp_path, contents)?;
+    rename(&temp_path, path).inspect_err(|_| {
+        let _ = remove_file(&temp_path);
+    })
+}
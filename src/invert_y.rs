@@ -1,4 +1,4 @@
-use egui::{Pos2, pos2};
+use emath::{Pos2, pos2};
 
 use crate::{right_tri_angle_edge_case::RIGHT_TRI, shape_container::ShapeContainer};
 
@@ -0,0 +1,274 @@
+//! The JSON form of a shroud, for scripts that would rather not parse Lua tables:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "layers": [
+//!     {
+//!       "shape": "SQUARE",
+//!       "offset": [1.0, 2.5, 0.01],
+//!       "size": [10.0, 5.0],
+//!       "angle": 0.7853982,
+//!       "taper": 0.5,
+//!       "tri_color_id": 0,
+//!       "tri_color1_id": 1,
+//!       "line_color_id": 2,
+//!       "mirror": 1
+//!     },
+//!     { "shape": 129, "offset": [1.0, -2.5, 0.01], "size": [10.0, 5.0], "mirror": 0 }
+//!   ],
+//!   "groups": [[0, 1]]
+//! }
+//! ```
+//!
+//! - `shape` is a vanilla shape name, or the number of a custom shape.
+//! - `offset` is `[x, y, z]` relative to the block, as in a shroud `.lua` file.
+//! - `size` is `[width, height]`.
+//! - `angle` is in radians.
+//! - `taper` only affects `SQUARE`.
+//! - The colour ids are 0, 1 or 2, as in a shroud `.lua` file.
+//! - `mirror` is the index of the layer this one mirrors. Giving it on one layer of a pair is enough.
+//! - `groups` are lists of layer indices. A layer can be in at most one group.
+//! - `comments` lists the `--` comment lines that go before a layer.
+//! - `extra_fields` lists `[key, value]` pairs that the editor does not understand. They are written
+//!   back as they are.
+//! - `trailing_comments` lists the comment lines that go after the last layer.
+//!
+//! Every layer field except `offset` may be left out, and then gets the same default as when it is
+//! left out of a shroud `.lua` file.
+
+use emath::Pos2;
+use luexks_reassembly::{
+    blocks::shroud_layer::ShroudLayerColor,
+    shapes::shape_id::ShapeId,
+    utility::{
+        angle::Angle,
+        display_oriented_math::{do2d_float_from, do3d_float_from},
+    },
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    export::shroud_layer_color_id,
+    layer_links::{LayerLinkError, assign_groups, link_mirror_pairs},
+    parse_shroud_text::apply_shroud_layer_shape,
+    shape_container::ShapeContainer,
+    shroud_layer_container::ShroudLayerContainer,
+};
+
+pub const JSON_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum JsonMessage {
+    #[error("Saved.")]
+    Saved,
+
+    #[error("Opened.")]
+    Opened,
+
+    #[error("Failed to read JSON: {0} :(")]
+    Json(String),
+
+    #[error("JSON version {0} is newer than this editor understands :(")]
+    UnsupportedVersion(u32),
+
+    #[error("Shroud layer {0} has colour id {1}, which is not 0, 1 or 2 :(")]
+    ColorId(usize, u8),
+
+    #[error("{0}")]
+    LayerLinks(LayerLinkError),
+
+    #[error("Could not open file :(")]
+    CouldNotOpenFile,
+
+    #[error("Could not write file :(")]
+    CouldNotWriteFile,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonShroud {
+    version: u32,
+    layers: Vec<JsonShroudLayer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<Vec<usize>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trailing_comments: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonShroudLayer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shape: Option<JsonShapeId>,
+    offset: [f32; 3],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<[f32; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    angle: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    taper: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tri_color_id: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tri_color1_id: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    line_color_id: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mirror: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    comments: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extra_fields: Vec<(String, String)>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonShapeId {
+    Custom(u32),
+    Vanilla(String),
+}
+
+/// Layers are moved by `-block_offset`, the same as when exporting a shroud.
+pub fn export_json(
+    shroud: &[ShroudLayerContainer],
+    groups: &[Vec<usize>],
+    trailing_comments: &[String],
+    block_offset: Pos2,
+) -> String {
+    let json_shroud = JsonShroud {
+        version: JSON_VERSION,
+        layers: shroud
+            .iter()
+            .map(|shroud_layer_container| {
+                let shroud_layer = &shroud_layer_container.shroud_layer;
+                let offset = shroud_layer.offset.as_ref().unwrap();
+                JsonShroudLayer {
+                    shape: shroud_layer.shape.as_ref().map(|shape| match shape {
+                        ShapeId::Number(number) => JsonShapeId::Custom(*number),
+                        _ => JsonShapeId::Vanilla(shape.to_string()),
+                    }),
+                    offset: [
+                        offset.x.to_f32() - block_offset.x,
+                        offset.y.to_f32() - block_offset.y,
+                        offset.z.to_f32(),
+                    ],
+                    size: shroud_layer
+                        .size
+                        .as_ref()
+                        .map(|size| [size.x.to_f32(), size.y.to_f32()]),
+                    angle: shroud_layer
+                        .angle
+                        .as_ref()
+                        .map(|angle| angle.as_radians().get_value()),
+                    taper: shroud_layer.taper,
+                    tri_color_id: shroud_layer.color_1.as_ref().map(shroud_layer_color_id),
+                    tri_color1_id: shroud_layer.color_2.as_ref().map(shroud_layer_color_id),
+                    line_color_id: shroud_layer.line_color.as_ref().map(shroud_layer_color_id),
+                    mirror: shroud_layer_container.mirror_index_option,
+                    comments: shroud_layer_container.comments.clone(),
+                    extra_fields: shroud_layer_container.unknown_fields.clone(),
+                }
+            })
+            .collect(),
+        groups: groups.to_vec(),
+        trailing_comments: trailing_comments.to_vec(),
+    };
+    serde_json::to_string_pretty(&json_shroud)
+        .expect("the JSON shroud only has string keys and finite numbers")
+}
+
+pub type JsonImport = (Vec<ShroudLayerContainer>, Vec<Vec<usize>>, Vec<String>);
+
+/// Returns the layers, moved by `block_offset`, the groups and the trailing comments.
+/// Shapes that are not in `loaded_shapes` get a placeholder, as with a shroud `.lua` file.
+pub fn import_json(
+    json_text: &str,
+    loaded_shapes: &[ShapeContainer],
+    block_offset: Pos2,
+) -> Result<JsonImport, JsonMessage> {
+    let json_shroud: JsonShroud =
+        serde_json::from_str(json_text).map_err(|err| JsonMessage::Json(err.to_string()))?;
+    if json_shroud.version > JSON_VERSION {
+        return Err(JsonMessage::UnsupportedVersion(json_shroud.version));
+    }
+    let mut mirror_pairs = Vec::new();
+    let mut shroud = json_shroud
+        .layers
+        .into_iter()
+        .enumerate()
+        .map(|(idx, json_shroud_layer)| {
+            let color = |color_id_option: Option<u8>| {
+                color_id_option
+                    .map(|color_id| {
+                        shroud_layer_color(color_id).ok_or(JsonMessage::ColorId(idx, color_id))
+                    })
+                    .transpose()
+            };
+            let [x, y, z] = json_shroud_layer.offset;
+            let mut shroud_layer_container = ShroudLayerContainer {
+                comments: json_shroud_layer.comments,
+                unknown_fields: json_shroud_layer.extra_fields,
+                ..Default::default()
+            };
+            let shroud_layer = &mut shroud_layer_container.shroud_layer;
+            shroud_layer.offset = Some(do3d_float_from(x + block_offset.x, y + block_offset.y, z));
+            if let Some([width, height]) = json_shroud_layer.size {
+                shroud_layer.size = Some(do2d_float_from(width, height));
+            }
+            if let Some(angle) = json_shroud_layer.angle {
+                shroud_layer.angle = Some(Angle::Radian(angle).as_degrees());
+            }
+            if let Some(taper) = json_shroud_layer.taper {
+                shroud_layer.taper = Some(taper);
+            }
+            if let Some(color_1) = color(json_shroud_layer.tri_color_id)? {
+                shroud_layer.color_1 = Some(color_1);
+            }
+            if let Some(color_2) = color(json_shroud_layer.tri_color1_id)? {
+                shroud_layer.color_2 = Some(color_2);
+            }
+            if let Some(line_color) = color(json_shroud_layer.line_color_id)? {
+                shroud_layer.line_color = Some(line_color);
+            }
+            if let Some(shape) = json_shroud_layer.shape {
+                let shape_name_string = match &shape {
+                    JsonShapeId::Custom(number) => number.to_string(),
+                    JsonShapeId::Vanilla(name) => name.clone(),
+                };
+                shroud_layer_container.shroud_layer.shape = Some(match shape {
+                    JsonShapeId::Custom(number) => ShapeId::Number(number),
+                    JsonShapeId::Vanilla(name) => ShapeId::Vanilla(name),
+                });
+                apply_shroud_layer_shape(
+                    &mut shroud_layer_container,
+                    shape_name_string,
+                    loaded_shapes,
+                );
+            }
+            if let Some(mirror_idx) = json_shroud_layer.mirror {
+                mirror_pairs.push((idx, mirror_idx));
+            }
+            Ok(shroud_layer_container)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // A mirror only has to be given on one layer of a pair, but both layers must agree.
+    link_mirror_pairs(&mut shroud, &mirror_pairs).map_err(JsonMessage::LayerLinks)?;
+    assign_groups(&mut shroud, &json_shroud.groups).map_err(JsonMessage::LayerLinks)?;
+    Ok((shroud, json_shroud.groups, json_shroud.trailing_comments))
+}
+
+fn shroud_layer_color(color_id: u8) -> Option<ShroudLayerColor> {
+    match color_id {
+        0 => Some(ShroudLayerColor::Color1),
+        1 => Some(ShroudLayerColor::Color2),
+        2 => Some(ShroudLayerColor::LineColor),
+        _ => None,
+    }
+}
+
+/// Whether `s` looks like JSON rather than a shroud `.lua` file, which never has a quoted key.
+pub fn is_json_text(s: &str) -> bool {
+    s.trim_start()
+        .strip_prefix('{')
+        .is_some_and(|s| s.trim_start().starts_with('"'))
+}
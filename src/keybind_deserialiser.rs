@@ -1,118 +1,110 @@
-use std::{fs::File, io::Read};
+use std::fs::read_to_string;
 
-use crate::{keybinds::Keybinds, shroud_editor::ShroudEditor};
-use egui::{Key, KeyboardShortcut, Modifiers};
-use nom::{
-    IResult, Parser,
-    branch::alt,
-    bytes::{complete::tag, take_until},
-    character::complete::newline,
-    combinator::{complete, map, map_parser, opt, rest, value},
-    sequence::delimited,
+use crate::{
+    config::{config_file_path, migrate_legacy_config_file},
+    keybinds::Keybinds,
 };
+use egui::{Key, KeyboardShortcut, Modifiers};
 
-impl ShroudEditor {
-    pub fn _try_load_keybinds(&mut self) -> Result<(), ()> {
-        let mut file = File::open("arthur.danskin").map_err(|_| ())?;
-        let mut s = String::new();
-        file.read_to_string(&mut s).map_err(|_| ())?;
-        let keybinds = deserialise_keybinds(&s).map_err(|_| ())?.1;
-        self.keybinds = keybinds;
-        Ok(())
+pub const KEYBINDS_FILE_NAME: &str = "keybinds";
+pub const KEYBINDS_FILE_HEADER: &str = "--Luexks Shroud Editor bindings";
+pub const KEYBINDS_FILE_VERSION: u32 = 1;
+/// Where bindings were saved before they moved into the config directory.
+const LEGACY_KEYBINDS_PATH: &str = "arthur.danskin";
+
+/// Returns the bindings, and the lines of the bindings file that could not be read.
+/// Any action without a readable line keeps its default binding.
+pub fn load_keybinds() -> (Keybinds, Vec<String>) {
+    migrate_legacy_config_file(LEGACY_KEYBINDS_PATH, KEYBINDS_FILE_NAME);
+    match read_to_string(config_file_path(KEYBINDS_FILE_NAME)) {
+        Ok(s) => deserialise_keybinds(&s),
+        Err(_) => (Keybinds::default(), Vec::new()),
     }
 }
 
-pub fn try_load_keybinds() -> Result<Keybinds, ()> {
-    let mut file = File::open("arthur.danskin").map_err(|_| ())?;
-    let mut s = String::new();
-    file.read_to_string(&mut s).map_err(|_| ())?;
-    Ok(deserialise_keybinds(&s).map_err(|_| ())?.1)
+/// Also reads the old positional format, `name binding` per line, so migrated files keep working.
+pub fn deserialise_keybinds(s: &str) -> (Keybinds, Vec<String>) {
+    let mut keybinds = Keybinds::default();
+    let mut unparsed_lines = Vec::new();
+    for line in s.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("--") {
+            continue;
+        }
+        let Some((name, binding)) = line.split_once('=').or_else(|| line.split_once(' ')) else {
+            unparsed_lines.push(line.to_string());
+            continue;
+        };
+        let (name, binding) = (name.trim(), binding.trim());
+        if name == "version" {
+            if binding
+                .parse::<u32>()
+                .is_ok_and(|version| version <= KEYBINDS_FILE_VERSION)
+            {
+                continue;
+            }
+            unparsed_lines.push(line.to_string());
+            continue;
+        }
+        let is_parsed = if let Some((_, binding_option)) = keybinds
+            .keyboard_bindings_mut()
+            .into_iter()
+            .find(|(binding_name, _)| *binding_name == name)
+        {
+            deserialise_keyboard_binding(binding)
+                .map(|binding| *binding_option = binding)
+                .is_some()
+        } else if let Some((_, binding_option)) = keybinds
+            .shortcut_bindings_mut()
+            .into_iter()
+            .find(|(binding_name, _)| *binding_name == name)
+        {
+            deserialise_shortcut_binding(binding)
+                .map(|binding| *binding_option = binding)
+                .is_some()
+        } else {
+            false
+        };
+        if !is_parsed {
+            unparsed_lines.push(line.to_string());
+        }
+    }
+    (keybinds, unparsed_lines)
 }
 
-fn deserialise_keybinds(s: &str) -> IResult<&str, Keybinds> {
-    let (s, pan_up) = deserialise_keyboard_binding(s, "up")?;
-    let (s, pan_down) = deserialise_keyboard_binding(s, "down")?;
-    let (s, pan_right) = deserialise_keyboard_binding(s, "right")?;
-    let (s, pan_left) = deserialise_keyboard_binding(s, "left")?;
-    let (s, copy) = deserialise_shortcut_binding(s, "yank")?;
-    let (s, paste) = deserialise_shortcut_binding(s, "paste")?;
-    let (s, mirror) = deserialise_shortcut_binding(s, "mirror")?;
-    let (s, delete) = deserialise_shortcut_binding(s, "delete")?;
-    let (s, undo) = deserialise_shortcut_binding(s, "undo")?;
-    let (s, redo) = deserialise_shortcut_binding(s, "redo")?;
-    let (s, group) = deserialise_shortcut_binding(s, "group")?;
-
-    Ok((
-        s,
-        Keybinds {
-            pan_up,
-            pan_down,
-            pan_right,
-            pan_left,
-            copy,
-            paste,
-            mirror,
-            delete,
-            undo,
-            redo,
-            group,
-            ..Default::default()
-        },
-    ))
+fn is_unbound(binding: &str) -> bool {
+    binding == "none" || binding.ends_with("in the Gamma Void")
 }
 
-fn deserialise_keyboard_binding<'a>(s: &'a str, name: &'a str) -> IResult<&'a str, Option<Key>> {
-    delimited(
-        (tag(name), tag(" ")),
-        alt((
-            value(None, tag("is in the Gamma Void")),
-            map(take_until("\n"), Key::from_name),
-        )),
-        newline,
-    )
-    .parse(s)
+/// `None` if the binding could not be read, `Some(None)` if the action is unbound.
+fn deserialise_keyboard_binding(binding: &str) -> Option<Option<Key>> {
+    if is_unbound(binding) {
+        Some(None)
+    } else {
+        Key::from_name(binding).map(Some)
+    }
 }
 
-fn deserialise_shortcut_binding<'a>(
-    s: &'a str,
-    name: &'a str,
-) -> IResult<&'a str, Option<KeyboardShortcut>> {
-    let (s, binding_option) = delimited(
-        (tag(name), tag(" ")),
-        alt((
-            value(None, tag("is in the Gamma Void")),
-            map_parser(
-                take_until("\n"),
-                map(
-                    (
-                        complete(opt(value((), tag("Ctrl+")))),
-                        complete(opt(value((), tag("Alt+")))),
-                        complete(opt(value((), tag("Shift+")))),
-                        complete(map(rest, Key::from_name)),
-                    ),
-                    Some,
-                ),
-            ),
-        )),
-        newline,
-    )
-    .parse(s)?;
-    Ok((
-        s,
-        match binding_option {
-            None => None,
-            Some((ctrl, alt, shift, key)) => key.map(|key| {
-                KeyboardShortcut::new(
-                    Modifiers {
-                        alt: alt.is_some(),
-                        ctrl: ctrl.is_some(),
-                        shift: shift.is_some(),
-                        mac_cmd: false,
-                        command: ctrl.is_some(),
-                    },
-                    key,
-                )
-            }),
-        },
-    ))
+fn deserialise_shortcut_binding(binding: &str) -> Option<Option<KeyboardShortcut>> {
+    if is_unbound(binding) {
+        return Some(None);
+    }
+    let (modifier_names, key_name) = match binding.rsplit_once('+') {
+        Some((modifier_names, key_name)) if !key_name.is_empty() => (modifier_names, key_name),
+        _ => ("", binding),
+    };
+    let key = Key::from_name(key_name)?;
+    let mut modifiers = Modifiers::NONE;
+    for modifier_name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+        match modifier_name {
+            "Ctrl" => {
+                modifiers.ctrl = true;
+                modifiers.command = true;
+            }
+            "Alt" => modifiers.alt = true,
+            "Shift" => modifiers.shift = true,
+            _ => return None,
+        }
+    }
+    Some(Some(KeyboardShortcut::new(modifiers, key)))
 }
@@ -1,58 +1,49 @@
-use std::{
-    fmt::Display,
-    fs::File,
-    io::{self, Write},
-};
+use std::{fmt::Display, io};
 
 use egui::{Key, KeyboardShortcut, ModifierNames};
 
-use crate::{keybinds::Keybinds, shroud_editor::ShroudEditor};
+use crate::{
+    config::write_config_file,
+    keybind_deserialiser::{KEYBINDS_FILE_HEADER, KEYBINDS_FILE_NAME, KEYBINDS_FILE_VERSION},
+    keybinds::Keybinds,
+    shroud_editor::ShroudEditor,
+};
 
 impl Display for Keybinds {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}{}{}{}{}{}{}{}{}{}{}",
-            format_keyboard_binding("up", &self.pan_up),
-            format_keyboard_binding("down", &self.pan_down),
-            format_keyboard_binding("right", &self.pan_right),
-            format_keyboard_binding("left", &self.pan_left),
-            format_keyboard_shortcut_binding("yank", &self.copy),
-            format_keyboard_shortcut_binding("paste", &self.paste),
-            format_keyboard_shortcut_binding("mirror", &self.mirror),
-            format_keyboard_shortcut_binding("delete", &self.delete),
-            format_keyboard_shortcut_binding("undo", &self.undo),
-            format_keyboard_shortcut_binding("redo", &self.redo),
-            format_keyboard_shortcut_binding("group", &self.group),
-        )
+        writeln!(f, "{KEYBINDS_FILE_HEADER}")?;
+        writeln!(f, "version={KEYBINDS_FILE_VERSION}")?;
+        for (name, binding_option) in self.keyboard_bindings() {
+            writeln!(f, "{}={}", name, format_keyboard_binding(binding_option))?;
+        }
+        for (name, binding_option) in self.shortcut_bindings() {
+            writeln!(
+                f,
+                "{}={}",
+                name,
+                format_keyboard_shortcut_binding(binding_option)
+            )?;
+        }
+        Ok(())
     }
 }
 
-fn format_keyboard_binding(name: &str, binding_option: &Option<Key>) -> String {
+fn format_keyboard_binding(binding_option: &Option<Key>) -> &str {
     match binding_option {
-        Some(binding) => format!("{} {}\n", name, binding.name()),
-        None => format!("{} in the Gamma Void\n", name),
+        Some(binding) => binding.name(),
+        None => "none",
     }
 }
 
-fn format_keyboard_shortcut_binding(
-    name: &str,
-    binding_option: &Option<KeyboardShortcut>,
-) -> String {
+fn format_keyboard_shortcut_binding(binding_option: &Option<KeyboardShortcut>) -> String {
     match binding_option {
-        Some(binding) => format!(
-            "{} {}\n",
-            name,
-            binding.format(&ModifierNames::NAMES, false)
-        ),
-        None => format!("{} is in the Gamma Void\n", name),
+        Some(binding) => binding.format(&ModifierNames::NAMES, false),
+        None => "none".to_string(),
     }
 }
 
 impl ShroudEditor {
     pub fn save_keybinds(&self) -> io::Result<()> {
-        let mut file = File::create("arthur.danskin")?;
-        write!(file, "{}", self.keybinds)?;
-        Ok(())
+        write_config_file(KEYBINDS_FILE_NAME, &self.keybinds.to_string())
     }
 }
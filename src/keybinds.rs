@@ -62,6 +62,52 @@ impl Default for Keybinds {
     }
 }
 
+/// Names used for each action in the bindings file. The order is only used when writing it.
+#[rustfmt::skip]
+impl Keybinds {
+    pub fn keyboard_bindings(&self) -> [(&'static str, &Option<Key>); 4] {
+        [
+            ("up",      &self.pan_up),
+            ("down",    &self.pan_down),
+            ("right",   &self.pan_right),
+            ("left",    &self.pan_left),
+        ]
+    }
+
+    pub fn keyboard_bindings_mut(&mut self) -> [(&'static str, &mut Option<Key>); 4] {
+        [
+            ("up",      &mut self.pan_up),
+            ("down",    &mut self.pan_down),
+            ("right",   &mut self.pan_right),
+            ("left",    &mut self.pan_left),
+        ]
+    }
+
+    pub fn shortcut_bindings(&self) -> [(&'static str, &Option<KeyboardShortcut>); 7] {
+        [
+            ("yank",    &self.copy),
+            ("paste",   &self.paste),
+            ("mirror",  &self.mirror),
+            ("delete",  &self.delete),
+            ("undo",    &self.undo),
+            ("redo",    &self.redo),
+            ("group",   &self.group),
+        ]
+    }
+
+    pub fn shortcut_bindings_mut(&mut self) -> [(&'static str, &mut Option<KeyboardShortcut>); 7] {
+        [
+            ("yank",    &mut self.copy),
+            ("paste",   &mut self.paste),
+            ("mirror",  &mut self.mirror),
+            ("delete",  &mut self.delete),
+            ("undo",    &mut self.undo),
+            ("redo",    &mut self.redo),
+            ("group",   &mut self.group),
+        ]
+    }
+}
+
 #[rustfmt::skip]
 impl ShroudEditor {
     pub fn binding_config(&mut self, ctx: &Context, ui: &mut Ui) {
@@ -70,8 +116,12 @@ impl ShroudEditor {
             .body_unindented(|ui| {
                 ui.label("Bindings are automatically loaded on startup from bindings file if one exists.");
                 ui.small("Apology: if your clipboard does not contain text, this program cannot detect Ctrl+V because my GUI framework is eating the paste event, sorry :(");
-                if ui.button("Save Bindings").clicked() {
-                    let _ = self.save_keybinds();
+                if ui.button("Save Bindings").clicked() && self.save_keybinds().is_ok() {
+                    self.keybinds_unparsed_lines.clear();
+                }
+                if !self.keybinds_unparsed_lines.is_empty() {
+                    ui.label("These lines of the bindings file could not be read, so their actions were left as the defaults:");
+                    self.keybinds_unparsed_lines.iter().for_each(|line| { ui.small(line); });
                 }
                 let keybinds = &mut self.keybinds;
                 ui.label("Click away to cancel, press escape to unbind.");
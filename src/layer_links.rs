@@ -0,0 +1,214 @@
+use thiserror::Error;
+
+use crate::shroud_layer_container::ShroudLayerContainer;
+
+/// Each group is the list of shroud layer indexes in it. Layers point back at their group through
+/// `group_idx_option`, and at their mirror through `mirror_index_option`.
+pub type Groups = Vec<Vec<usize>>;
+
+/// Why mirrors or groups read from a file cannot be linked up.
+#[derive(Error, Debug, PartialEq)]
+pub enum LayerLinkError {
+    #[error("Shroud layer {0} mirrors shroud layer {1}, which does not exist :(")]
+    MirrorIndex(usize, usize),
+
+    #[error("Shroud layer {0} mirrors itself :(")]
+    SelfMirror(usize),
+
+    #[error("Shroud layer {0} mirrors both shroud layer {1} and shroud layer {2} :(")]
+    ConflictingMirror(usize, usize, usize),
+
+    #[error("Group refers to shroud layer {0}, which does not exist :(")]
+    LayerIndex(usize),
+
+    #[error("Shroud layer {0} is in both group {1} and group {2} :(")]
+    GroupMembership(usize, usize, usize),
+}
+
+/// Links both layers of every pair to each other. A pair may be given more than once, or once
+/// each way, but a layer can only ever mirror one other layer.
+pub fn link_mirror_pairs(
+    shroud: &mut [ShroudLayerContainer],
+    mirror_pairs: &[(usize, usize)],
+) -> Result<(), LayerLinkError> {
+    for &(idx, mirror_idx) in mirror_pairs {
+        if mirror_idx >= shroud.len() {
+            return Err(LayerLinkError::MirrorIndex(idx, mirror_idx));
+        }
+        if idx >= shroud.len() {
+            return Err(LayerLinkError::MirrorIndex(mirror_idx, idx));
+        }
+        if idx == mirror_idx {
+            return Err(LayerLinkError::SelfMirror(idx));
+        }
+        for (a, b) in [(idx, mirror_idx), (mirror_idx, idx)] {
+            match shroud[a].mirror_index_option {
+                Some(other_idx) if other_idx != b => {
+                    return Err(LayerLinkError::ConflictingMirror(a, other_idx, b));
+                }
+                _ => shroud[a].mirror_index_option = Some(b),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Points each layer at the group it is listed in, which must be at most one.
+pub fn assign_groups(
+    shroud: &mut [ShroudLayerContainer],
+    groups: &Groups,
+) -> Result<(), LayerLinkError> {
+    for (group_idx, group) in groups.iter().enumerate() {
+        for layer_idx in group {
+            let layer = shroud
+                .get_mut(*layer_idx)
+                .ok_or(LayerLinkError::LayerIndex(*layer_idx))?;
+            if let Some(other_group_idx) = layer.group_idx_option {
+                return Err(LayerLinkError::GroupMembership(
+                    *layer_idx,
+                    other_group_idx,
+                    group_idx,
+                ));
+            }
+            layer.group_idx_option = Some(group_idx);
+        }
+    }
+    Ok(())
+}
+
+/// Removes the layers at `layer_idxs` and fixes up every mirror and group link that pointed past
+/// them. Mirrors of removed layers that are kept lose their mirror link.
+pub fn remove_shroud_layers(
+    shroud: &mut Vec<ShroudLayerContainer>,
+    groups: &mut Groups,
+    layer_idxs: &[usize],
+) {
+    layer_idxs.iter().for_each(|layer_idx| {
+        if let Some(mirror_index) = shroud[*layer_idx].mirror_index_option
+            && !layer_idxs.contains(&mirror_index)
+        {
+            shroud[mirror_index].mirror_index_option = None;
+        }
+    });
+    let mut layer_idxs = layer_idxs.to_vec();
+    layer_idxs.sort_unstable();
+    layer_idxs.iter().rev().for_each(|layer_idx| {
+        shroud.remove(*layer_idx);
+        mirror_idx_logic_for_deleted_layer_idx(shroud, *layer_idx);
+        groups_logic_for_deleted_layer_idx(groups, *layer_idx);
+    });
+    cull_groups(shroud, groups);
+}
+
+pub fn groups_logic_for_deleted_layer_idx(groups: &mut Groups, layer_idx: usize) {
+    groups.iter_mut().for_each(|group| {
+        if let Some(group_layer_idx_idx) = group
+            .iter()
+            .position(|group_layer_idx| *group_layer_idx == layer_idx)
+        {
+            group.remove(group_layer_idx_idx);
+        }
+        group.iter_mut().for_each(|group_layer_idx| {
+            if *group_layer_idx > layer_idx {
+                *group_layer_idx -= 1;
+            }
+        });
+    });
+}
+
+pub fn mirror_idx_logic_for_deleted_layer_idx(
+    shroud: &mut [ShroudLayerContainer],
+    layer_idx: usize,
+) {
+    shroud.iter_mut().for_each(|shroud_layer_container| {
+        if let Some(mirror_index) = &mut shroud_layer_container.mirror_index_option
+            && *mirror_index > layer_idx
+        {
+            *mirror_index -= 1;
+        }
+    });
+}
+
+/// Drops groups left with fewer than two layers.
+pub fn cull_groups(shroud: &mut [ShroudLayerContainer], groups: &mut Groups) {
+    groups
+        .iter()
+        .enumerate()
+        .rev()
+        .for_each(|(groups_group_idx, group)| {
+            if group.is_empty() {
+                layer_group_idx_logic_for_deleted_groups_group_idx(shroud, groups_group_idx);
+            } else if group.len() == 1 {
+                shroud[group[0]].group_idx_option = None;
+                layer_group_idx_logic_for_deleted_groups_group_idx(shroud, groups_group_idx);
+            }
+        });
+    groups.retain(|group| group.len() >= 2);
+}
+
+pub fn layer_group_idx_logic_for_deleted_groups_group_idx(
+    shroud: &mut [ShroudLayerContainer],
+    groups_group_idx: usize,
+) {
+    shroud.iter_mut().for_each(|layer| {
+        if let Some(layer_group_idx) = &mut layer.group_idx_option
+            && *layer_group_idx > groups_group_idx
+        {
+            *layer_group_idx -= 1;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shroud(len: usize) -> Vec<ShroudLayerContainer> {
+        (0..len).map(|_| ShroudLayerContainer::default()).collect()
+    }
+
+    #[test]
+    fn links_mirror_pairs_both_ways() {
+        let mut shroud = shroud(4);
+        link_mirror_pairs(&mut shroud, &[(0, 2), (2, 0), (3, 1)]).unwrap();
+        assert_eq!(
+            shroud
+                .iter()
+                .map(|layer| layer.mirror_index_option)
+                .collect::<Vec<_>>(),
+            vec![Some(2), Some(3), Some(0), Some(1)]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_mirror_pairs() {
+        assert_eq!(
+            link_mirror_pairs(&mut shroud(2), &[(0, 2)]),
+            Err(LayerLinkError::MirrorIndex(0, 2))
+        );
+        assert_eq!(
+            link_mirror_pairs(&mut shroud(2), &[(1, 1)]),
+            Err(LayerLinkError::SelfMirror(1))
+        );
+        assert_eq!(
+            link_mirror_pairs(&mut shroud(3), &[(0, 1), (2, 1)]),
+            Err(LayerLinkError::ConflictingMirror(1, 0, 2))
+        );
+    }
+
+    #[test]
+    fn assigns_groups() {
+        let mut shroud = shroud(3);
+        assign_groups(&mut shroud, &vec![vec![2, 0]]).unwrap();
+        assert_eq!(shroud[0].group_idx_option, Some(0));
+        assert_eq!(shroud[1].group_idx_option, None);
+        assert_eq!(
+            assign_groups(&mut shroud(3), &vec![vec![0, 1], vec![1, 2]]),
+            Err(LayerLinkError::GroupMembership(1, 0, 1))
+        );
+        assert_eq!(
+            assign_groups(&mut shroud(3), &vec![vec![0, 3]]),
+            Err(LayerLinkError::LayerIndex(3))
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! The shroud document model, the `shroud={...}` and shapes parsers, export and the layer
+//! transforms used by the editor tools. Nothing in here depends on egui, so it can be used from
+//! other tooling as well as the editor.
+
+use emath::Pos2;
+
+pub mod add_mirror;
+pub mod color_type_conversion;
+pub mod export;
+pub mod export_format;
+pub mod invert_y;
+pub mod json_interchange;
+pub mod layer_links;
+pub mod mirror_pairs;
+pub mod object_snap;
+pub mod parse_shapes_text;
+pub mod parse_shroud_text;
+pub mod parsing;
+pub mod pos_and_display_oriented_number_conversion;
+pub mod project_format;
+pub mod restructure_vertices;
+pub mod right_tri_angle_edge_case;
+pub mod rotation_edgecase;
+pub mod shape_container;
+pub mod shroud_layer_container;
+pub mod source_location;
+pub mod transform;
+
+/// Stands in for a shape that is not loaded, until it is.
+pub const MISSING_SHAPE_PLACEHOLDER: [Pos2; 8] = [
+    Pos2::new(5.0, -2.0),
+    Pos2::new(2.0, -5.0),
+    Pos2::new(-2.0, -5.0),
+    Pos2::new(-5.0, -2.0),
+    Pos2::new(-5.0, 2.0),
+    Pos2::new(-2.0, 5.0),
+    Pos2::new(2.0, 5.0),
+    Pos2::new(5.0, 2.0),
+];
+
+pub const DEFAULT_SQUARE: [Pos2; 4] = [
+    Pos2::new(5.0, -5.0),
+    Pos2::new(-5.0, -5.0),
+    Pos2::new(-5.0, 5.0),
+    Pos2::new(5.0, 5.0),
+];
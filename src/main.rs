@@ -3,49 +3,45 @@
 #![allow(rustdoc::missing_crate_level_docs)] // it's an example
 
 use eframe::egui;
+use luexks_shroud_editor::{
+    DEFAULT_SQUARE, add_mirror, color_type_conversion, export, export_format, invert_y,
+    json_interchange, layer_links, mirror_pairs, object_snap, parse_shapes_text, parse_shroud_text,
+    parsing, pos_and_display_oriented_number_conversion, project_format, restructure_vertices,
+    right_tri_angle_edge_case, rotation_edgecase, shape_container, shroud_layer_container,
+    source_location, transform,
+};
 mod shroud_editor;
-use egui::Pos2;
 use shroud_editor::*;
 mod angle_gizmo;
 mod block_container;
-mod color_type_conversion;
+mod cli;
+mod config;
 mod file_import_export;
 mod fonts;
 mod gui;
 mod icon_radius;
-mod invert_y;
 mod keybind_deserialiser;
 mod keybind_serialiser;
 mod keybinds;
-mod mirror_pairs;
-mod pos_and_display_oriented_number_conversion;
 mod pos_in_polygon;
 mod position_conversion;
 mod reference_image;
-mod restructure_vertices;
-mod right_tri_angle_edge_case;
-mod rotation_edgecase;
 mod selection_mirror_splitting;
 mod selection_type;
-mod shape_container;
 mod shapes_import_text_default;
 mod shroud_import_text_default;
 mod shroud_interaction;
-mod shroud_layer_container;
 mod size_from_verts;
 mod snap_to_grid;
 mod styles;
 mod undo_redo;
 mod verts_to_convex_hull;
 
-pub const DEFAULT_SQUARE: [Pos2; 4] = [
-    Pos2::new(5.0, -5.0),
-    Pos2::new(-5.0, -5.0),
-    Pos2::new(-5.0, 5.0),
-    Pos2::new(5.0, 5.0),
-];
-
 fn main() -> eframe::Result {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let Some(exit_code) = cli::run(&args) {
+        std::process::exit(exit_code);
+    }
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1920.0 / 2.0, 1080.0 / 2.0]),
@@ -59,7 +55,10 @@ fn main() -> eframe::Result {
             egui_extras::install_image_loaders(&cc.egui_ctx);
 
             // Ok(Box::<ShroudEditor>::default())
-            Ok(Box::new(ShroudEditor::default()))
+            let mut shroud_editor = ShroudEditor::default();
+            shroud_editor.load_preferences();
+            shroud_editor.start_recovery();
+            Ok(Box::new(shroud_editor))
         }),
     )
 }
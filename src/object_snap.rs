@@ -0,0 +1,59 @@
+use emath::{Pos2, Vec2};
+
+/// The vertices, edge midpoints and centre of a polygon, which are what a moving layer snaps by.
+pub fn snap_points(vertices: &[Pos2], centre: Pos2) -> Vec<Pos2> {
+    vertices
+        .iter()
+        .copied()
+        .chain(edges(vertices).map(|(a, b)| a.lerp(b, 0.5)))
+        .chain([centre])
+        .collect()
+}
+
+/// Finds the nearest of `moving_points` to a vertex of `outlines` within `tolerance`, or failing
+/// that, to an edge of `outlines`. Returns how far to move to line them up, and the point snapped to.
+pub fn object_snap(
+    moving_points: &[Pos2],
+    outlines: &[Vec<Pos2>],
+    tolerance: f32,
+) -> Option<(Vec2, Pos2)> {
+    let nearest = |targets: &mut dyn Iterator<Item = (Pos2, Pos2)>| {
+        targets
+            .map(|(moving_point, target)| (moving_point.distance(target), moving_point, target))
+            .filter(|(distance, _, _)| *distance <= tolerance)
+            .min_by(|(distance_1, _, _), (distance_2, _, _)| distance_1.total_cmp(distance_2))
+            .map(|(_, moving_point, target)| (target - moving_point, target))
+    };
+    nearest(&mut moving_points.iter().flat_map(|moving_point| {
+        outlines
+            .iter()
+            .flatten()
+            .map(move |vertex| (*moving_point, *vertex))
+    }))
+    .or_else(|| {
+        nearest(&mut moving_points.iter().flat_map(|moving_point| {
+            outlines.iter().flat_map(move |outline| {
+                edges(outline).map(move |(a, b)| {
+                    (*moving_point, closest_point_on_segment(*moving_point, a, b))
+                })
+            })
+        }))
+    })
+}
+
+fn edges(vertices: &[Pos2]) -> impl Iterator<Item = (Pos2, Pos2)> + '_ {
+    vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+}
+
+fn closest_point_on_segment(point: Pos2, a: Pos2, b: Pos2) -> Pos2 {
+    let ab = b - a;
+    let length_sq = ab.length_sq();
+    if length_sq == 0.0 {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    a + ab * t
+}
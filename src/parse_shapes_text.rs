@@ -0,0 +1,379 @@
+use std::num::ParseIntError;
+
+use luexks_reassembly::{
+    shapes::{scale::Scale, shape::Shape, shape_id::ShapeId, vertex::Vertex, vertices::Vertices},
+    utility::display_oriented_math::do2d_float_from,
+};
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::{complete::tag, take_until},
+    character::complete::{alphanumeric0, digit1},
+    combinator::{complete, map, map_res, opt, recognize},
+    error::{FromExternalError, ParseError},
+    multi::{many0, many1},
+    sequence::{preceded, separated_pair},
+};
+use parse_vanilla_shapes::VANILLA_SHAPE_COUNT;
+use thiserror::Error;
+
+use crate::{
+    mirror_pairs::MirrorPairs,
+    parsing::{brackets_around, parse_number_expression, ws, ws_and_equals, ws_around},
+    shape_container::ShapeContainer,
+    source_location::SourceLocation,
+};
+
+#[derive(Error, Debug)]
+pub enum ShapesMessage {
+    #[error("YES!")]
+    Success,
+
+    #[error("{0}")]
+    Debug(String),
+
+    #[error(
+        "This is a 'the input for a part of the parser was incomplete' type error, but it shouldn't occur."
+    )]
+    Incomplete,
+
+    #[error("Failed to parse the shape={{ part of it :(")]
+    Shapes,
+
+    #[error("Failed to parse shape :(")]
+    Shape,
+
+    #[error("Failed to parse scale :(")]
+    Scale,
+
+    #[error("Failed to parse verts :(")]
+    Verts,
+
+    #[error("Failed to parse vert :(")]
+    Vert,
+
+    #[error("For shape {0}, its mirror_of shape with ID {1} could not be found :(")]
+    MirrorOfNotFound(String, String),
+
+    #[error("For shape {0}, its mirror_of shape with ID {1} is another mirror :(")]
+    MirrorOfIsAMirror(String, String),
+
+    #[error("Parse error for the number {0}. It's probably out of range :(")]
+    NumberParse(String),
+
+    #[error("Could not open file :(")]
+    CouldNotOpenFile,
+
+    #[error("{0} ({1})")]
+    Located(Box<ShapesMessage>, SourceLocation),
+}
+
+impl ShapesMessage {
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            ShapesMessage::Located(_, location) => Some(location),
+            _ => None,
+        }
+    }
+}
+
+/// The error the parsers fail with, which keeps where the failure was so it can be located.
+struct ShapesParseError<'a> {
+    input: &'a str,
+    message: ShapesMessage,
+}
+
+impl<'a> ShapesParseError<'a> {
+    /// Only names the failure if something deeper has not already.
+    fn or_message(self, message: ShapesMessage) -> Self {
+        match self.message {
+            ShapesMessage::Debug(_) => ShapesParseError { message, ..self },
+            _ => self,
+        }
+    }
+}
+
+impl<'a> FromExternalError<&'a str, ParseIntError> for ShapesParseError<'a> {
+    fn from_external_error(
+        input: &'a str,
+        _kind: nom::error::ErrorKind,
+        _e: ParseIntError,
+    ) -> Self {
+        ShapesParseError {
+            input,
+            message: ShapesMessage::NumberParse(input.to_string()),
+        }
+    }
+}
+
+impl<'a> ParseError<&'a str> for ShapesParseError<'a> {
+    fn from_error_kind(input: &'a str, kind: nom::error::ErrorKind) -> Self {
+        ShapesParseError {
+            input,
+            message: ShapesMessage::Debug(format!("Cry {kind:?}")),
+        }
+    }
+
+    fn append(_input: &'a str, _kind: nom::error::ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+pub fn parse_shapes_text(
+    input: &str,
+) -> Result<(Vec<ShapeContainer>, MirrorPairs, Vec<usize>), ShapesMessage> {
+    match shapes(input) {
+        Ok((_, (shapes, mirror_pairs, non_mirrors))) => Ok((shapes, mirror_pairs, non_mirrors)),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) if e.input.is_empty() => Err(e.message),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ShapesMessage::Located(
+            Box::new(e.message),
+            SourceLocation::new(input, e.input, "shape"),
+        )),
+        Err(nom::Err::Incomplete(_)) => Err(ShapesMessage::Incomplete),
+    }
+}
+
+type ShapesResult<'a, O> = IResult<&'a str, O, ShapesParseError<'a>>;
+
+fn shapes(input: &str) -> ShapesResult<'_, (Vec<ShapeContainer>, MirrorPairs, Vec<usize>)> {
+    let (remainder, mut shapes) = preceded(ws_around(tag("{")), many1(ws_around(shape)))
+        .parse(input)
+        .map_err(|e| e.map(|e| e.or_message(ShapesMessage::Shapes)))?;
+    shapes_end(remainder)?;
+    shapes.retain(|shape| {
+        matches!(shape.s, Shape::Mirror { .. }) || shape.s.get_first_scale_vertices().0.len() >= 3
+    });
+    let mut non_mirrors =
+        (VANILLA_SHAPE_COUNT..VANILLA_SHAPE_COUNT + shapes.len()).collect::<Vec<_>>();
+    let mut mirror_pairs = MirrorPairs::new();
+    for shape_idx in 0..shapes.len() {
+        if let Shape::Mirror { id, mirror_of, .. } = &shapes[shape_idx].s {
+            if let Some(mirror_of_idx) = shapes
+                .iter()
+                .position(|shape| *mirror_of == shape.s.get_id().unwrap())
+            {
+                if let Shape::Standard {
+                    id: _mirror_id,
+                    scales,
+                } = &shapes[mirror_of_idx].s
+                {
+                    shapes[shape_idx].s = Shape::Standard {
+                        id: id.clone(),
+                        scales: scales
+                            .iter()
+                            .cloned()
+                            .map(|scale| Scale {
+                                verts: scale.verts.mirror(),
+                                ..scale
+                            })
+                            .collect(),
+                    };
+                    let shape_idx = VANILLA_SHAPE_COUNT + shape_idx;
+                    let mirror_of_idx = VANILLA_SHAPE_COUNT + mirror_of_idx;
+                    mirror_pairs.push((shape_idx, mirror_of_idx));
+                    non_mirrors.retain(|non_mirror_idx| {
+                        *non_mirror_idx != shape_idx && *non_mirror_idx != mirror_of_idx
+                    });
+                } else {
+                    return Err(nom::Err::Error(ShapesParseError {
+                        input: "",
+                        message: ShapesMessage::MirrorOfIsAMirror(
+                            id.to_string(),
+                            mirror_of.to_string(),
+                        ),
+                    }));
+                }
+            } else {
+                return Err(nom::Err::Error(ShapesParseError {
+                    input: "",
+                    message: ShapesMessage::MirrorOfNotFound(id.to_string(), mirror_of.to_string()),
+                }));
+            }
+        }
+    }
+    Ok((remainder, (shapes, mirror_pairs, non_mirrors)))
+}
+
+enum ScalesOrMirrorOf<'a> {
+    Scales(Vec<(Scale, Option<&'a str>)>),
+    MirrorOf(u32),
+}
+
+/// A shape that fails to parse stops `many1` rather than failing the whole list, so anything left
+/// before the closing brace is a broken shape.
+fn shapes_end(input: &str) -> ShapesResult<'_, ()> {
+    if let Ok((remainder, _)) = ws_around(tag::<_, _, ShapesParseError>("}")).parse(input) {
+        return Ok((remainder, ()));
+    }
+    let (input, _) = ws(input)?;
+    shape(input)?;
+    Err(nom::Err::Error(ShapesParseError {
+        input,
+        message: ShapesMessage::Shape,
+    }))
+}
+
+fn shape(input: &str) -> ShapesResult<'_, ShapeContainer> {
+    let (remainder, (id_str, _, scales_or_mirror_of, _)) = brackets_around((
+        ws_around(digit1),
+        opt((radial_launcher, ws)),
+        alt((
+            brackets_around(map(many1(ws_around(scale)), |scales| {
+                ScalesOrMirrorOf::Scales(scales)
+            })),
+            map(
+                preceded(
+                    (
+                        ws_around(brackets_around(ws)),
+                        tag("mirror_of"),
+                        ws_and_equals,
+                    ),
+                    map_res(digit1, str::parse::<u32>),
+                ),
+                ScalesOrMirrorOf::MirrorOf,
+            ),
+        )),
+        (opt((ws, radial_launcher)), ws),
+    ))
+    .parse(input)
+    .map_err(|e| e.map(|e| e.or_message(ShapesMessage::Shape)))?;
+    let id = match id_str.parse::<u32>() {
+        Ok(id) => ShapeId::Number(id),
+        Err(..) => {
+            return Err(nom::Err::Error(ShapesParseError {
+                input: id_str,
+                message: ShapesMessage::NumberParse(id_str.to_string()),
+            }));
+        }
+    };
+    match scales_or_mirror_of {
+        ScalesOrMirrorOf::Scales(scales_and_ports) => {
+            let (scales, scale_ports) = scales_and_ports
+                .into_iter()
+                .map(|(scale, ports_option)| (scale, ports_option.map(str::to_string)))
+                .unzip();
+            Ok((
+                remainder,
+                ShapeContainer {
+                    scale_ports,
+                    ..ShapeContainer::new(Shape::Standard { scales, id })
+                },
+            ))
+        }
+        ScalesOrMirrorOf::MirrorOf(mirror_of) => Ok((
+            remainder,
+            ShapeContainer::new(Shape::Mirror {
+                id,
+                mirror_of: ShapeId::Number(mirror_of),
+                scale_count: 0,
+                scale_names: Vec::new(),
+            }),
+        )),
+    }
+}
+
+fn scale(input: &str) -> ShapesResult<'_, (Scale, Option<&str>)> {
+    let (remainder, (verts, (_, ports_option))) = brackets_around(ws_around(preceded(
+        (take_until("verts"), tag("verts"), ws_and_equals),
+        (
+            verts,
+            (
+                ws,
+                opt(map(
+                    complete((
+                        take_until("ports"),
+                        tag("ports"),
+                        ws_and_equals,
+                        recognize(brackets_around(many0(ws_around(brackets_around(
+                            take_until("}"),
+                        ))))),
+                    )),
+                    |(_, _, _, ports)| ports,
+                )),
+            ),
+        ),
+    )))
+    .parse(input)
+    .map_err(|e| e.map(|e| e.or_message(ShapesMessage::Scale)))?;
+    Ok((
+        remainder,
+        (
+            Scale {
+                verts,
+                ..Default::default()
+            },
+            ports_option,
+        ),
+    ))
+}
+
+fn verts(input: &str) -> ShapesResult<'_, Vertices> {
+    let (remainder, verts) = brackets_around(many1(ws_around(vert)))
+        .parse(input)
+        .map_err(|e| e.map(|e| e.or_message(ShapesMessage::Verts)))?;
+    Ok((remainder, Vertices(verts)))
+}
+
+fn vert(input: &str) -> ShapesResult<'_, Vertex> {
+    let (remainder, (x, y)) = brackets_around(ws_around(separated_pair(
+        number_expression,
+        ws,
+        number_expression,
+    )))
+    .parse(input)
+    .map_err(|e| e.map(|e| e.or_message(ShapesMessage::Vert)))?;
+    Ok((remainder, Vertex(do2d_float_from(x, y))))
+}
+
+/// `parse_number_expression` with the failure position kept.
+fn number_expression(input: &str) -> ShapesResult<'_, f32> {
+    parse_number_expression(input).map_err(|e| {
+        e.map(|e| ShapesParseError {
+            input: e.input,
+            message: ShapesMessage::Debug(format!("Cry {:?}", e.code)),
+        })
+    })
+}
+
+fn radial_launcher(input: &str) -> ShapesResult<'_, ()> {
+    let (remainder, _) = (tag("radial_launcher")).parse(input)?;
+    let (remainder, _) = ws_and_equals(remainder)?;
+    let (remainder, _) = alphanumeric0.parse(remainder)?;
+    Ok((remainder, ()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shapes_and_mirrors() {
+        let text = "{\n\t{200 { {verts={{0,0}{10,0}{0,10}}} } }\n\t{201 {} mirror_of=200}\n}";
+        let (shapes, mirror_pairs, non_mirrors) = parse_shapes_text(text).unwrap();
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(
+            mirror_pairs,
+            vec![(VANILLA_SHAPE_COUNT + 1, VANILLA_SHAPE_COUNT)]
+        );
+        assert!(non_mirrors.is_empty());
+    }
+
+    #[test]
+    fn error_has_source_location() {
+        let text = "{\n\t{200 { {verts={{0,0}{10,0}{0,10}}} } }\n\t{201 { {verts=oops} } }\n}";
+        let Err(ShapesMessage::Located(_, location)) = parse_shapes_text(text) else {
+            panic!("expected a located error");
+        };
+        assert_eq!(location.line, 3);
+        assert_eq!(location.entry_idx_option, Some(1));
+    }
+
+    #[test]
+    fn missing_mirror_of_is_an_error() {
+        let text = "{\n\t{201 {} mirror_of=200}\n}";
+        assert!(matches!(
+            parse_shapes_text(text),
+            Err(ShapesMessage::MirrorOfNotFound(..))
+        ));
+    }
+}
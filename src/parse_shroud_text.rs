@@ -0,0 +1,574 @@
+use luexks_reassembly::{
+    blocks::shroud_layer::ShroudLayerColor,
+    shapes::shape_id::ShapeId,
+    utility::{
+        angle::Angle,
+        display_oriented_math::{do2d_float_from, do3d_float_from},
+    },
+};
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{consumed, map, peek},
+    multi::many0,
+    sequence::{pair, terminated},
+};
+use thiserror::Error;
+
+use crate::{
+    MISSING_SHAPE_PLACEHOLDER,
+    parsing::{
+        opaque_value, parse_number_expression, variable_name, variable_value, ws, ws_and_equals,
+        ws_keeping_comments,
+    },
+    restructure_vertices::restructure_vertices,
+    shape_container::ShapeContainer,
+    shroud_layer_container::ShroudLayerContainer,
+    source_location::SourceLocation,
+};
+
+#[derive(Error, Debug)]
+pub enum ShroudParseResult {
+    #[error("YES!")]
+    Success,
+
+    #[error("Imported what could be read, with {0} problems :|")]
+    SuccessWithProblems(usize),
+
+    #[error("Failed to find a shroud={{...}}, a block with one, or any shroud layers :(")]
+    Shroud,
+
+    #[error("Failed to parse shroud layer :(")]
+    ShroudLayer,
+
+    #[error("{0} ({1})")]
+    Located(Box<ShroudParseResult>, SourceLocation),
+
+    #[error("Failed to get variable value data for: `{0}` :(")]
+    VariableValueData(String),
+
+    #[error("Failed to parse tri_color_id: `{0}` :(")]
+    Color1(String),
+
+    #[error("Failed to parse tri_color1_id: `{0}` :(")]
+    Color2(String),
+
+    #[error("Failed to parse line_color_id: `{0}` :(")]
+    LineColor(String),
+
+    #[error("Failed to parse angle: `{0}` :(")]
+    Angle(String),
+
+    #[error("Failed to parse offset: `x: {0} y: {1} z: {2}` :(")]
+    Offset(String, String, String),
+
+    #[error("Failed to parse size: `width: {0} height: {1}` :(")]
+    Size(String, String),
+
+    #[error("Failed to parse taper: `{0}` :(")]
+    Taper(String),
+
+    #[error("Parse error for the number {0}. It's probably out of range :(")]
+    NumberParse(String),
+
+    #[error("Could not open file :(")]
+    CouldNotOpenFile,
+}
+
+impl ShroudParseResult {
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            ShroudParseResult::Located(_, location) => Some(location),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the layers, and the `--` comments after the last layer, which have no layer to be attached to.
+/// Fails with the first problem found; see `parse_shroud_text_lenient` for getting all of them.
+pub fn parse_shroud_text(
+    shroud_text: &str,
+    loaded_shapes: &[ShapeContainer],
+) -> Result<(Vec<ShroudLayerContainer>, Vec<String>), ShroudParseResult> {
+    let (shroud, trailing_comments, mut problems) =
+        parse_shroud_text_lenient(shroud_text, loaded_shapes);
+    if problems.is_empty() {
+        Ok((shroud, trailing_comments))
+    } else {
+        Err(problems.remove(0))
+    }
+}
+
+/// Parses every layer it can. Layers that are not valid syntax are skipped, and fields that
+/// cannot be read are left as their defaults. Every problem is returned, in the order they
+/// appear in the text.
+pub fn parse_shroud_text_lenient(
+    shroud_text: &str,
+    loaded_shapes: &[ShapeContainer],
+) -> (
+    Vec<ShroudLayerContainer>,
+    Vec<String>,
+    Vec<ShroudParseResult>,
+) {
+    // Layers are not always at the same depth, so their index is not left to `SourceLocation`.
+    let locate = |error: ShroudParseResult, at: &str, shroud_layer_idx_option: Option<usize>| {
+        let mut location = SourceLocation::new(shroud_text, at, "shroud layer");
+        location.entry_idx_option = shroud_layer_idx_option.or(location.entry_idx_option);
+        ShroudParseResult::Located(Box::new(error), location)
+    };
+    let (shroud_data, trailing_comments) = match shroud(shroud_text) {
+        Ok((_, shroud_data)) => shroud_data,
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            return (
+                Vec::new(),
+                Vec::new(),
+                vec![locate(ShroudParseResult::Shroud, err.input, None)],
+            );
+        }
+        Err(nom::Err::Incomplete(_)) => {
+            return (Vec::new(), Vec::new(), vec![ShroudParseResult::Shroud]);
+        }
+    };
+    let mut shroud = Vec::new();
+    let mut problems = Vec::new();
+    for (shroud_layer_idx, shroud_layer_data_result) in shroud_data.into_iter().enumerate() {
+        let (comments, shroud_layer_data) = match shroud_layer_data_result {
+            Ok(shroud_layer_data) => shroud_layer_data,
+            Err(at) => {
+                problems.push(locate(
+                    ShroudParseResult::ShroudLayer,
+                    at,
+                    Some(shroud_layer_idx),
+                ));
+                continue;
+            }
+        };
+        let mut shroud_layer_container = ShroudLayerContainer {
+            comments,
+            ..Default::default()
+        };
+        for variable_data in &shroud_layer_data {
+            if let Err((error, at)) = apply_shroud_layer_variable(
+                &mut shroud_layer_container,
+                variable_data,
+                loaded_shapes,
+            ) {
+                problems.push(locate(error, at, Some(shroud_layer_idx)));
+            }
+        }
+        if shroud_layer_container.shroud_layer.offset.is_none() {
+            shroud_layer_container.shroud_layer.offset = Some(do3d_float_from(0.0, 0.0, 0.0));
+        }
+        shroud.push(shroud_layer_container);
+    }
+    (shroud, trailing_comments, problems)
+}
+
+#[rustfmt::skip]
+fn apply_shroud_layer_variable<'a>(shroud_layer_container: &mut ShroudLayerContainer, variable_data: &ShroudLayerVariable<'a>, loaded_shapes: &[ShapeContainer]) -> Result<(), (ShroudParseResult, &'a str)> {
+    match variable_data {
+        ("tri_color_id", variable_value_data, _) => {
+            if let Some(tri_color_id_data) = variable_value_data.first() {
+                match *tri_color_id_data {
+                    "0" => { shroud_layer_container.shroud_layer.color_1 = Some(ShroudLayerColor::Color1); },
+                    "1" => { shroud_layer_container.shroud_layer.color_1 = Some(ShroudLayerColor::Color2); },
+                    "2" => { shroud_layer_container.shroud_layer.color_1 = Some(ShroudLayerColor::LineColor); },
+                    _ => { return Err((ShroudParseResult::Color1(tri_color_id_data.to_string()), *tri_color_id_data)) },
+                }
+            } else {
+                return Err((ShroudParseResult::VariableValueData("tri_color_id".to_string()), variable_data.0));
+            }
+        },
+        ("tri_color1_id", variable_value_data, _) => {
+            if let Some(tri_color1_id_data) = variable_value_data.first() {
+                match *tri_color1_id_data {
+                    "0" => { shroud_layer_container.shroud_layer.color_2 = Some(ShroudLayerColor::Color1); },
+                    "1" => { shroud_layer_container.shroud_layer.color_2 = Some(ShroudLayerColor::Color2); },
+                    "2" => { shroud_layer_container.shroud_layer.color_2 = Some(ShroudLayerColor::LineColor); },
+                    _ => { return Err((ShroudParseResult::Color2(tri_color1_id_data.to_string()), *tri_color1_id_data)) },
+                }
+            } else {
+                return Err((ShroudParseResult::VariableValueData("tri_color1_id".to_string()), variable_data.0));
+            }
+        },
+        ("line_color_id", variable_value_data, _) => {
+            if let Some(line_color_id_data) = variable_value_data.first() {
+                match *line_color_id_data {
+                    "0" => { shroud_layer_container.shroud_layer.line_color = Some(ShroudLayerColor::Color1); },
+                    "1" => { shroud_layer_container.shroud_layer.line_color = Some(ShroudLayerColor::Color2); },
+                    "2" => { shroud_layer_container.shroud_layer.line_color = Some(ShroudLayerColor::LineColor); },
+                    _ => { return Err((ShroudParseResult::LineColor(line_color_id_data.to_string()), *line_color_id_data)) },
+                }
+            } else {
+                return Err((ShroudParseResult::VariableValueData("line_color_id".to_string()), variable_data.0));
+            }
+        },
+        ("shape", variable_value_data, _) => {
+            if let Some(shape_data) = variable_value_data.first() {
+                if shape_data.chars().all(|c| c.is_ascii_digit()) {
+                    let shape_name = shape_data.parse::<u32>().map_err(|_| (ShroudParseResult::NumberParse(shape_data.to_string()), *shape_data))?;
+                    shroud_layer_container.shroud_layer.shape = Some(ShapeId::Number(shape_name));
+                    apply_shroud_layer_shape(shroud_layer_container, shape_name.to_string(), loaded_shapes);
+                } else {
+                    shroud_layer_container.shroud_layer.shape = Some(ShapeId::Vanilla(shape_data.to_string()));
+                    apply_shroud_layer_shape(shroud_layer_container, shape_data.to_string(), loaded_shapes);
+                }
+            } else {
+                return Err((ShroudParseResult::VariableValueData("shape".to_string()), variable_data.0));
+            }
+        },
+        ("angle", variable_value_data, _) => {
+            if let Some(angle_data) = variable_value_data.first() {
+                if let Ok((_, angle)) = parse_number_expression(angle_data) {
+                    shroud_layer_container.shroud_layer.angle = Some(Angle::Radian(angle).as_degrees());
+                } else {
+                    return Err((ShroudParseResult::Angle(angle_data.to_string()), *angle_data));
+                }
+            } else {
+                return Err((ShroudParseResult::VariableValueData("angle".to_string()), variable_data.0));
+            }
+        },
+        ("offset", variable_value_data, _) => {
+            if let (
+                Some(x_data),
+                Some(y_data),
+                Some(z_data),
+            ) = (
+                variable_value_data.get(0),
+                variable_value_data.get(1),
+                variable_value_data.get(2),
+            ) {
+                if let (
+                    Ok((_, x)),
+                    Ok((_, y)),
+                    Ok((_, z)),
+                ) = (
+                    parse_number_expression(x_data),
+                    parse_number_expression(y_data),
+                    parse_number_expression(z_data),
+                ) {
+                    shroud_layer_container.shroud_layer.offset = Some(do3d_float_from(x, y, z));
+                } else {
+                    return Err((ShroudParseResult::Offset(x_data.to_string(), y_data.to_string(), z_data.to_string()), *x_data));
+                }
+            } else {
+                return Err((ShroudParseResult::VariableValueData("offset".to_string()), variable_data.0));
+            }
+        },
+        ("size", variable_value_data, _) => {
+            if let (
+                Some(width_data),
+                Some(height_data),
+            ) = (
+                variable_value_data.get(0),
+                variable_value_data.get(1),
+            ) {
+                if let (
+                    Ok((_, width)),
+                    Ok((_, height)),
+                ) = (
+                    parse_number_expression(width_data),
+                    parse_number_expression(height_data),
+                ) {
+                    shroud_layer_container.shroud_layer.size = Some(do2d_float_from(width, height));
+                } else {
+                    return Err((ShroudParseResult::Size(width_data.to_string(), height_data.to_string()), *width_data));
+                }
+            } else {
+                return Err((ShroudParseResult::VariableValueData("size".to_string()), variable_data.0));
+            }
+        },
+        ("taper", variable_value_data, _) => {
+            if let Some(taper_data) = variable_value_data.first() {
+                if let Ok((_, taper)) = parse_number_expression(taper_data) {
+                    shroud_layer_container.shroud_layer.taper = Some(taper);
+                } else {
+                    return Err((ShroudParseResult::Taper(taper_data.to_string()), *taper_data));
+                }
+            } else {
+                return Err((ShroudParseResult::VariableValueData("taper".to_string()), variable_data.0)) ;
+            }
+        },
+        (variable_name, _, raw_value) => { shroud_layer_container.unknown_fields.push((variable_name.to_string(), raw_value.to_string())); },
+    }
+    Ok(())
+}
+
+/// A shape that is not loaded gets a placeholder, which `load_shapes` replaces once it is.
+pub(crate) fn apply_shroud_layer_shape(
+    shroud_layer_container: &mut ShroudLayerContainer,
+    shape_name_string: String,
+    loaded_shapes: &[ShapeContainer],
+) {
+    if let Some(matched_shape) = match_shape(loaded_shapes, &shape_name_string) {
+        shroud_layer_container.vertices =
+            restructure_vertices(matched_shape.s.get_first_scale_vertices());
+        shroud_layer_container.invert_height_of_mirror = matched_shape.invert_height_of_mirror;
+        shroud_layer_container.shape_is_missing = false;
+    } else {
+        shroud_layer_container.vertices = MISSING_SHAPE_PLACEHOLDER.into();
+        shroud_layer_container.shape_is_missing = true;
+    }
+    shroud_layer_container.shape_id = shape_name_string;
+}
+
+fn match_shape<'a>(
+    loaded_shapes: &'a [ShapeContainer],
+    shape_name_string: &'a str,
+) -> Option<&'a ShapeContainer> {
+    loaded_shapes
+        .iter()
+        .find(|loaded_shape| loaded_shape.s.get_id().unwrap().to_string() == shape_name_string)
+}
+
+/// A variable's name, the values it was read as, and the text of its value.
+type ShroudLayerVariable<'a> = (&'a str, Vec<&'a str>, &'a str);
+
+type ShroudLayerData<'a> = (Vec<String>, Vec<ShroudLayerVariable<'a>>);
+
+/// Values that are neither numbers nor names, like strings, are read as no values, so that a
+/// variable the editor does not know can still be kept.
+fn shroud_layer_variable(input: &str) -> IResult<&str, ShroudLayerVariable<'_>> {
+    let (remainder, name) = variable_name(input)?;
+    let (remainder, _) = ws_and_equals(remainder)?;
+    let (remainder, (raw_value, values)) = alt((
+        terminated(consumed(variable_value), value_end),
+        map(opaque_value, |raw_value| (raw_value, Vec::new())),
+    ))
+    .parse(remainder)?;
+    Ok((remainder, (name, values, raw_value)))
+}
+
+/// So that a value like `0x10` is kept whole rather than read as `0` followed by junk.
+fn value_end(input: &str) -> IResult<&str, ()> {
+    if input.is_empty()
+        || input.starts_with("--")
+        || input.starts_with(|c: char| c.is_whitespace() || c == ',' || c == '}')
+    {
+        Ok((input, ()))
+    } else {
+        Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )))
+    }
+}
+
+/// Comments inside a layer's braces are kept with the ones before it.
+fn shroud_layer_container(input: &str) -> IResult<&str, ShroudLayerData<'_>> {
+    let (remainder, mut comments) = ws_keeping_comments(input)?;
+    let (remainder, _) = tag("{")(remainder)?;
+    let (remainder, variables) =
+        many0(pair(ws_keeping_comments, shroud_layer_variable)).parse(remainder)?;
+    let (remainder, last_comments) = ws_keeping_comments(remainder)?;
+    let (remainder, _) = tag("}")(remainder)?;
+    let variables = variables
+        .into_iter()
+        .map(|(variable_comments, variable)| {
+            comments.extend(variable_comments);
+            variable
+        })
+        .collect();
+    comments.extend(last_comments);
+    Ok((remainder, (comments, variables)))
+}
+
+type ShroudData<'a> = (Vec<Result<ShroudLayerData<'a>, &'a str>>, Vec<String>);
+
+/// Besides `shroud={...}`, this reads a block table or anything else with a `shroud` field in it,
+/// a list of layers in braces, and bare layers separated by commas or newlines.
+fn shroud(input: &str) -> IResult<&str, ShroudData<'_>> {
+    let (remainder, _) = ws(input)?;
+    let layers_start = if let Some(shroud_field) = find_shroud_field(remainder) {
+        let (shroud_field, _) = tag("shroud")(shroud_field)?;
+        let (shroud_field, _) = ws_and_equals(shroud_field)?;
+        let (layers_start, _) = tag("{")(shroud_field)?;
+        layers_start
+    } else if let Ok((layers_start, _)) = (
+        tag::<_, _, nom::error::Error<&str>>("{"),
+        ws,
+        peek(tag("{")),
+    )
+        .parse(remainder)
+    {
+        layers_start
+    } else {
+        let (after_layers, shroud_data) = shroud_layers(remainder)?;
+        if shroud_data.0.is_empty() {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                remainder,
+                nom::error::ErrorKind::Tag,
+            )));
+        }
+        return Ok((after_layers, shroud_data));
+    };
+    shroud_layers(layers_start)
+}
+
+/// Reads layers up to the closing brace of the table they are in, or the end of the text.
+/// A layer that is not valid syntax is kept as where in it the parse failed, and skipped over.
+fn shroud_layers(mut remainder: &str) -> IResult<&str, ShroudData<'_>> {
+    let mut shroud_data = Vec::new();
+    loop {
+        let (layers_remainder, layers) = many0(shroud_layer_container).parse(remainder)?;
+        shroud_data.extend(layers.into_iter().map(Ok));
+        remainder = layers_remainder;
+        let (_, trailing_comments) = ws_keeping_comments(remainder)?;
+        let (after_ws, _) = ws(remainder)?;
+        if after_ws.is_empty() || after_ws.starts_with('}') {
+            return Ok((after_ws, (shroud_data, trailing_comments)));
+        }
+        shroud_data.push(Err(match shroud_layer_container(remainder) {
+            Err(nom::Err::Error(err) | nom::Err::Failure(err)) => err.input,
+            _ => after_ws,
+        }));
+        match skip_broken_shroud_layer(after_ws) {
+            Some(after_broken_layer) if after_broken_layer.len() < after_ws.len() => {
+                remainder = after_broken_layer;
+            }
+            _ => return Ok(("", (shroud_data, Vec::new()))),
+        }
+    }
+}
+
+/// The first `shroud={` in the text, from `shroud`. Comments and strings are skipped.
+fn find_shroud_field(input: &str) -> Option<&str> {
+    let mut chars = input.char_indices();
+    let mut is_after_name_char = false;
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '-' if input[idx..].starts_with("--") => {
+                chars.by_ref().find(|(_, c)| *c == '\n');
+            }
+            '"' => {
+                chars.by_ref().find(|(_, c)| *c == '"');
+            }
+            's' if !is_after_name_char
+                && (
+                    tag::<_, _, nom::error::Error<&str>>("shroud"),
+                    ws_and_equals,
+                    tag("{"),
+                )
+                    .parse(&input[idx..])
+                    .is_ok() =>
+            {
+                return Some(&input[idx..]);
+            }
+            _ => {}
+        }
+        is_after_name_char = c.is_alphanumeric() || c == '_';
+    }
+    None
+}
+
+/// Skips to the end of the table starting at `input`, or to the next table if `input` is not
+/// the start of one. Braces in `--` comments are ignored.
+fn skip_broken_shroud_layer(input: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut chars = input.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '-' if input[idx..].starts_with("--") => {
+                chars.by_ref().find(|(_, c)| *c == '\n');
+            }
+            '{' if depth == 0 && idx > 0 => return Some(&input[idx..]),
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(&input[idx..]),
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&input[idx + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_layers_and_trailing_comments() {
+        let text = "shroud={\n\t{ offset={1,-2/4,0.5} taper=0.5 }\n\t{ size={3,4} foo=\"bar\" }\n\t-- the end\n}";
+        let (shroud, trailing_comments) = parse_shroud_text(text, &[]).unwrap();
+        assert_eq!(shroud.len(), 2);
+        let offset = shroud[0].shroud_layer.offset.as_ref().unwrap();
+        assert_eq!(
+            (offset.x.to_f32(), offset.y.to_f32(), offset.z.to_f32()),
+            (1.0, -0.5, 0.5)
+        );
+        assert_eq!(shroud[0].shroud_layer.taper, Some(0.5));
+        assert_eq!(
+            shroud[1].unknown_fields,
+            vec![("foo".to_string(), "\"bar\"".to_string())]
+        );
+        assert_eq!(trailing_comments, vec!["\t-- the end".to_string()]);
+    }
+
+    #[test]
+    fn error_has_source_location() {
+        let text = "shroud={\n\t{ offset={0,0,0} }\n\t{ tri_color_id=7 }\n}";
+        let Err(ShroudParseResult::Located(error, location)) = parse_shroud_text(text, &[]) else {
+            panic!("expected a located error");
+        };
+        assert!(matches!(*error, ShroudParseResult::Color1(ref id) if id == "7"));
+        assert_eq!((location.line, location.column), (3, 17));
+        assert_eq!(location.entry_idx_option, Some(1));
+    }
+
+    #[test]
+    fn no_shroud_is_an_error() {
+        assert!(matches!(
+            parse_shroud_text("", &[]),
+            Err(ShroudParseResult::Located(error, _)) if matches!(*error, ShroudParseResult::Shroud)
+        ));
+    }
+
+    #[test]
+    fn lenient_keeps_layers_with_bad_fields() {
+        let text =
+            "shroud={\n\t{ tri_color_id=7 taper=0.25 }\n\t{ offset={1,2} }\n\t{ taper=0.5 }\n}";
+        let (shroud, _, problems) = parse_shroud_text_lenient(text, &[]);
+        assert_eq!(shroud.len(), 3);
+        assert_eq!(shroud[0].shroud_layer.taper, Some(0.25));
+        assert_eq!(shroud[2].shroud_layer.taper, Some(0.5));
+        assert_eq!(problems.len(), 2);
+        assert!(matches!(&problems[0], ShroudParseResult::Located(error, _)
+            if matches!(**error, ShroudParseResult::Color1(_))));
+        assert!(matches!(&problems[1], ShroudParseResult::Located(error, _)
+            if matches!(**error, ShroudParseResult::VariableValueData(_))));
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.location().unwrap().entry_idx_option)
+                .collect::<Vec<_>>(),
+            vec![Some(0), Some(1)]
+        );
+    }
+
+    #[test]
+    fn lenient_skips_broken_layers() {
+        let text = "shroud={\n\t{ taper=0.5 }\n\t{ = }\n\t{ taper=0.25 }\n}";
+        let (shroud, _, problems) = parse_shroud_text_lenient(text, &[]);
+        assert_eq!(shroud.len(), 2);
+        assert_eq!(shroud[1].shroud_layer.taper, Some(0.25));
+        assert_eq!(problems.len(), 1);
+        let Some(ShroudParseResult::Located(error, location)) = problems.first() else {
+            panic!("expected a located problem");
+        };
+        assert!(matches!(**error, ShroudParseResult::ShroudLayer));
+        assert_eq!(location.line, 3);
+        assert_eq!(location.entry_idx_option, Some(1));
+    }
+
+    #[test]
+    fn lenient_without_shroud_has_one_problem() {
+        let (shroud, trailing_comments, problems) = parse_shroud_text_lenient("", &[]);
+        assert!(shroud.is_empty() && trailing_comments.is_empty());
+        assert_eq!(problems.len(), 1);
+    }
+}
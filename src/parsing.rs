@@ -0,0 +1,332 @@
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::{
+        complete::{tag, take_while, take_while1},
+        take_until,
+    },
+    character::complete::{char, multispace0, one_of, satisfy},
+    combinator::{map, not, opt, peek, recognize, value, verify},
+    error::{Error, ErrorKind, ParseError},
+    multi::{many0, many1, separated_list1},
+    number::complete::float,
+    sequence::{delimited, pair, preceded, separated_pair, terminated},
+};
+use std::f32::{
+    self,
+    consts::{PI, TAU},
+};
+
+/// Evaluates a Lua style arithmetic expression, like `-pi/4 + 0.1` or `sqrt(2)*3`.
+/// Only whitespace may separate its parts, never commas, so `{1, -2}` is still two numbers.
+pub fn parse_number_expression(input: &str) -> IResult<&str, f32> {
+    binary_operations(input, &['+', '-'], term)
+}
+
+/// Requires the whole of `s` to be the expression.
+pub fn evaluate_number_expression(s: &str) -> Option<f32> {
+    match parse_number_expression(s.trim()) {
+        Ok(("", number)) => Some(number),
+        _ => None,
+    }
+}
+
+fn term(input: &str) -> IResult<&str, f32> {
+    binary_operations(input, &['*', '/', '%'], unary)
+}
+
+fn binary_operations<'a>(
+    input: &'a str,
+    operators: &[char],
+    operand: fn(&'a str) -> IResult<&'a str, f32>,
+) -> IResult<&'a str, f32> {
+    let (remainder, first_number) = operand(input)?;
+    let (remainder, operator_number_pairs) = many0(pair(
+        preceded(
+            multispace0,
+            verify(operator, |operator_char| operators.contains(operator_char)),
+        ),
+        preceded(multispace0, operand),
+    ))
+    .parse(remainder)?;
+    Ok((
+        remainder,
+        operator_number_pairs
+            .into_iter()
+            .fold(first_number, |acc, (operator, number)| match operator {
+                '+' => acc + number,
+                '-' => acc - number,
+                '*' => acc * number,
+                '/' => acc / number,
+                _ => acc - (acc / number).floor() * number,
+            }),
+    ))
+}
+
+/// A `-` followed by another `-` starts a comment rather than being an operator.
+fn operator(input: &str) -> IResult<&str, char> {
+    alt((terminated(char('-'), not(char('-'))), one_of("+*/%^"))).parse(input)
+}
+
+/// Like Lua, `^` binds tighter than unary minus, so `-2^2` is `-4`.
+fn unary(input: &str) -> IResult<&str, f32> {
+    alt((
+        map(
+            preceded((terminated(char('-'), not(char('-'))), multispace0), unary),
+            |number: f32| -number,
+        ),
+        power,
+    ))
+    .parse(input)
+}
+
+fn power(input: &str) -> IResult<&str, f32> {
+    let (remainder, base) = atom(input)?;
+    let (remainder, exponent_option) =
+        opt(preceded((multispace0, char('^'), multispace0), unary)).parse(remainder)?;
+    Ok((
+        remainder,
+        exponent_option.map_or(base, |exponent| base.powf(exponent)),
+    ))
+}
+
+fn atom(input: &str) -> IResult<&str, f32> {
+    alt((
+        preceded(
+            peek(satisfy(|c: char| c.is_ascii_digit() || c == '.')),
+            float,
+        ),
+        delimited(
+            (char('('), multispace0),
+            parse_number_expression,
+            (multispace0, char(')')),
+        ),
+        constant_or_function,
+    ))
+    .parse(input)
+}
+
+/// Names may have Lua's `math.` in front of them.
+fn constant_or_function(input: &str) -> IResult<&str, f32> {
+    let (remainder, name) =
+        take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '.')(input)?;
+    let name = name.to_ascii_lowercase();
+    let name = name.strip_prefix("math.").unwrap_or(&name);
+    match name {
+        "pi" => return Ok((remainder, PI)),
+        "tau" => return Ok((remainder, TAU)),
+        "huge" => return Ok((remainder, f32::INFINITY)),
+        _ => {}
+    }
+    let (remainder, arguments) = delimited(
+        (multispace0, char('('), multispace0),
+        separated_list1(
+            (multispace0, char(','), multispace0),
+            parse_number_expression,
+        ),
+        (multispace0, char(')')),
+    )
+    .parse(remainder)?;
+    let number_option = match (name, &arguments[..]) {
+        ("sqrt", [x]) => Some(x.sqrt()),
+        ("abs", [x]) => Some(x.abs()),
+        ("floor", [x]) => Some(x.floor()),
+        ("ceil", [x]) => Some(x.ceil()),
+        ("sin", [x]) => Some(x.sin()),
+        ("cos", [x]) => Some(x.cos()),
+        ("tan", [x]) => Some(x.tan()),
+        ("asin", [x]) => Some(x.asin()),
+        ("acos", [x]) => Some(x.acos()),
+        ("atan", [x]) => Some(x.atan()),
+        ("atan", [y, x]) | ("atan2", [y, x]) => Some(y.atan2(*x)),
+        ("rad", [x]) => Some(x.to_radians()),
+        ("deg", [x]) => Some(x.to_degrees()),
+        ("exp", [x]) => Some(x.exp()),
+        ("log", [x]) => Some(x.ln()),
+        ("pow", [x, y]) => Some(x.powf(*y)),
+        ("min", arguments) => arguments.iter().copied().reduce(f32::min),
+        ("max", arguments) => arguments.iter().copied().reduce(f32::max),
+        _ => None,
+    };
+    number_option
+        .map(|number| (remainder, number))
+        .ok_or(nom::Err::Error(Error::new(input, ErrorKind::Verify)))
+}
+
+pub fn variable_name(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+pub fn variable_value(input: &str) -> IResult<&str, Vec<&str>> {
+    if peek(char::<&str, Error<_>>('{')).parse(input).is_ok() {
+        brackets_around(many1(ws_around(alphanumeric_special_1))).parse(input)
+    } else {
+        alphanumeric_special_1
+            .parse(input)
+            .map(|x| (x.0, vec![x.1]))
+    }
+}
+
+/// A whole number expression, or else a name.
+pub fn alphanumeric_special_1(input: &str) -> IResult<&str, &str> {
+    alt((
+        recognize(parse_number_expression),
+        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+    ))
+    .parse(input)
+}
+
+pub fn ws<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, (), E> {
+    let (remainder, _) = many0(alt((
+        value((), take_while1(|c: char| c.is_whitespace() || c == ',')),
+        value((), comment),
+    )))
+    .parse(input)?;
+    Ok((remainder, ()))
+}
+
+/// Like `ws`, but keeps the `--` comments it skips, each with the indentation before it on its line.
+pub fn ws_keeping_comments(input: &str) -> IResult<&str, Vec<String>> {
+    let (remainder, comments) = many0(alt((
+        map(
+            recognize((take_while(|c: char| c == ' ' || c == '\t'), comment)),
+            |comment: &str| Some(comment.trim_end().to_string()),
+        ),
+        value(
+            None,
+            take_while1(|c: char| (c.is_whitespace() && c != ' ' && c != '\t') || c == ','),
+        ),
+        value(None, take_while1(|c: char| c == ' ' || c == '\t')),
+    )))
+    .parse(input)?;
+    Ok((remainder, comments.into_iter().flatten().collect()))
+}
+
+pub fn ws_and_equals<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, (), E> {
+    value((), (ws, value((), tag("=")), ws)).parse(input)
+}
+
+pub fn variable(input: &str) -> IResult<&str, (&str, Vec<&str>)> {
+    separated_pair(variable_name, ws_and_equals, variable_value).parse(input)
+}
+
+/// A value that is kept as written rather than read: a quoted string, a whole table, or else
+/// everything up to the next separator.
+pub fn opaque_value(input: &str) -> IResult<&str, &str> {
+    alt((
+        quoted_string,
+        table,
+        take_while1(|c: char| !c.is_whitespace() && !matches!(c, ',' | '{' | '}')),
+    ))
+    .parse(input)
+}
+
+fn quoted_string(input: &str) -> IResult<&str, &str> {
+    recognize((char('"'), take_while(|c: char| c != '"'), char('"'))).parse(input)
+}
+
+fn table(input: &str) -> IResult<&str, &str> {
+    recognize(brackets_around(many0(alt((
+        quoted_string,
+        table,
+        take_while1(|c: char| !matches!(c, '"' | '{' | '}')),
+    )))))
+    .parse(input)
+}
+
+pub fn comment<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, (), E> {
+    value((), (tag("--"), take_until("\n"))).parse(input)
+}
+
+pub fn ws_around<'a, O, F, E>(inner: F) -> impl Parser<&'a str, Output = O, Error = E>
+where
+    F: Parser<&'a str, Output = O, Error = E>,
+    E: nom::error::ParseError<&'a str>,
+{
+    delimited(ws, inner, ws)
+}
+
+pub fn brackets_around<'a, O, F, E>(inner: F) -> impl Parser<&'a str, Output = O, Error = E>
+where
+    F: Parser<&'a str, Output = O, Error = E>,
+    E: nom::error::ParseError<&'a str>,
+{
+    delimited(char('{'), inner, char('}'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_evaluates_to(s: &str, expected: f32) {
+        let number = evaluate_number_expression(s).unwrap_or_else(|| panic!("`{s}` did not parse"));
+        assert!(
+            (number - expected).abs() < 1e-5,
+            "`{s}` evaluated to {number}, not {expected}"
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        assert_evaluates_to("1 + 2 * 3", 7.0);
+        assert_evaluates_to("10 - 4 - 3", 3.0);
+        assert_evaluates_to("12 / 3 / 2", 2.0);
+        assert_evaluates_to("2 * 3 ^ 2", 18.0);
+        assert_evaluates_to("2 ^ 3 ^ 2", 512.0);
+        assert_evaluates_to("7 % 3 + 1", 2.0);
+        assert_evaluates_to("-7 % 3", 2.0);
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_evaluates_to("-2", -2.0);
+        assert_evaluates_to("- -2", 2.0);
+        assert_evaluates_to("-2^2", -4.0);
+        assert_evaluates_to("2^-1", 0.5);
+        assert_evaluates_to("3 * -2", -6.0);
+        assert_evaluates_to("-pi/4 + 0.1", -PI / 4.0 + 0.1);
+    }
+
+    #[test]
+    fn parentheses() {
+        assert_evaluates_to("(1 + 2) * 3", 9.0);
+        assert_evaluates_to("( ( 2 ) )", 2.0);
+        assert_evaluates_to("-(1 - 3)", 2.0);
+        assert_evaluates_to("(-2)^2", 4.0);
+    }
+
+    #[test]
+    fn constants_and_functions() {
+        assert_evaluates_to("sqrt(2)*3", 2f32.sqrt() * 3.0);
+        assert_evaluates_to("math.max(1, 5, 3)", 5.0);
+        assert_evaluates_to("atan2(1, 1)", PI / 4.0);
+        assert_evaluates_to("TAU", TAU);
+    }
+
+    #[test]
+    fn stops_at_separators() {
+        assert_eq!(parse_number_expression("1, -2"), Ok((", -2", 1.0)));
+        assert_eq!(
+            parse_number_expression("1 -- comment"),
+            Ok((" -- comment", 1.0))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        [
+            "",
+            "1 +",
+            "* 2",
+            "(1 + 2",
+            "1 + 2)",
+            "sqrt(1, 2)",
+            "nope(1)",
+            "pi pi",
+            "1 2",
+            "--2",
+        ]
+        .into_iter()
+        .for_each(|s| assert_eq!(evaluate_number_expression(s), None, "`{s}` parsed"));
+    }
+}
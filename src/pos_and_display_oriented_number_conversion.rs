@@ -1,4 +1,4 @@
-use egui::{Pos2, pos2};
+use emath::{Pos2, pos2};
 use luexks_reassembly::utility::display_oriented_math::{
     DisplayOriented2D, DisplayOriented3D, do2d_float_from, do3d_float_from,
 };
@@ -0,0 +1,451 @@
+//! The `.shroudproj` project file format. The editor settings, block and reference image tables
+//! are only split into names and values here, as what they mean is up to the editor.
+
+use std::fmt::Write as _;
+
+use emath::{Pos2, pos2};
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::{tag, take_until, take_while, take_while1},
+    character::complete::{digit1, multispace0},
+    combinator::{map, map_res, opt},
+    multi::{many0, many1},
+    sequence::{delimited, preceded},
+};
+use thiserror::Error;
+
+use crate::{
+    export::shroud_layer_color_id,
+    layer_links::{LayerLinkError, assign_groups, link_mirror_pairs},
+    mirror_pairs::MirrorPairs,
+    parse_shapes_text::{ShapesMessage, parse_shapes_text},
+    parse_shroud_text::{ShroudParseResult, parse_shroud_text},
+    parsing::{brackets_around, variable_name, ws, ws_and_equals, ws_around},
+    shape_container::ShapeContainer,
+    shroud_layer_container::ShroudLayerContainer,
+};
+
+pub const PROJECT_FILE_EXTENSION: &str = "shroudproj";
+pub const PROJECT_FILE_VERSION: u32 = 1;
+pub const PROJECT_FILE_HEADER: &str = "--Luexks Shroud Editor project";
+
+#[derive(Error, Debug)]
+pub enum ProjectFileMessage {
+    #[error("Saved.")]
+    Saved,
+
+    #[error("Opened.")]
+    Opened,
+
+    #[error("Could not open file :(")]
+    CouldNotOpenFile,
+
+    #[error("Could not write file :(")]
+    CouldNotWriteFile,
+
+    #[error("Not a shroud editor project file :(")]
+    NotAProject,
+
+    #[error("Project file version {0} is newer than this editor understands :(")]
+    UnsupportedVersion(u32),
+
+    #[error("Failed to load the project's shapes: {0}")]
+    Shapes(ShapesMessage),
+
+    #[error("Failed to load the project's shroud: {0}")]
+    Shroud(ShroudParseResult),
+
+    #[error("Failed to find the block's shape in loaded shape list: `{0}` :(")]
+    BlockShape(String),
+
+    #[error("Mirror pair {0} does not have exactly two shroud layers :(")]
+    MirrorPair(usize),
+
+    #[error("{0}")]
+    LayerLinks(LayerLinkError),
+}
+
+pub type Table<'a> = Vec<(&'a str, Vec<&'a str>)>;
+
+/// A project file split into its sections, each still unparsed beyond its own syntax.
+pub struct ProjectSections<'a> {
+    pub version: u32,
+    pub editor: Table<'a>,
+    pub block: Table<'a>,
+    pub reference_image: Table<'a>,
+    pub reference_image_path_option: Option<&'a str>,
+    pub mirrors: Vec<Vec<usize>>,
+    pub groups: Vec<Vec<usize>>,
+    pub shapes: &'a str,
+    pub shroud: &'a str,
+}
+
+pub fn parse_project(s: &str) -> Result<ProjectSections<'_>, ProjectFileMessage> {
+    let (_, sections) = project(s).map_err(|_| ProjectFileMessage::NotAProject)?;
+    if sections.version > PROJECT_FILE_VERSION {
+        return Err(ProjectFileMessage::UnsupportedVersion(sections.version));
+    }
+    Ok(sections)
+}
+
+/// The project's custom shapes, their mirror pairs and the shapes that are not mirrors, as
+/// `parse_shapes_text` returns them.
+pub fn parse_project_shapes(
+    sections: &ProjectSections,
+) -> Result<(Vec<ShapeContainer>, MirrorPairs, Vec<usize>), ProjectFileMessage> {
+    if sections.shapes.trim().is_empty() {
+        Ok((Vec::new(), Vec::new(), Vec::new()))
+    } else {
+        parse_shapes_text(sections.shapes).map_err(ProjectFileMessage::Shapes)
+    }
+}
+
+/// The project's shroud and trailing comments, with its mirrors linked and groups assigned.
+pub fn parse_project_shroud(
+    sections: &ProjectSections,
+    loaded_shapes: &[ShapeContainer],
+) -> Result<(Vec<ShroudLayerContainer>, Vec<String>), ProjectFileMessage> {
+    let (mut shroud, trailing_comments) =
+        parse_shroud_text(sections.shroud, loaded_shapes).map_err(ProjectFileMessage::Shroud)?;
+    let mirror_pairs = sections
+        .mirrors
+        .iter()
+        .enumerate()
+        .map(|(mirror_pair_idx, mirror_pair)| match mirror_pair[..] {
+            [a, b] => Ok((a, b)),
+            _ => Err(ProjectFileMessage::MirrorPair(mirror_pair_idx)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    link_mirror_pairs(&mut shroud, &mirror_pairs).map_err(ProjectFileMessage::LayerLinks)?;
+    assign_groups(&mut shroud, &sections.groups).map_err(ProjectFileMessage::LayerLinks)?;
+    Ok((shroud, trailing_comments))
+}
+
+/// The header and version lines that every project file starts with.
+pub fn project_header() -> String {
+    format!("{PROJECT_FILE_HEADER}\nversion={PROJECT_FILE_VERSION}\n")
+}
+
+/// Writes the sections after the tables: mirrors, groups, shapes and the shroud itself.
+pub fn write_project_shroud(
+    s: &mut String,
+    shapes_text: &str,
+    shroud: &[ShroudLayerContainer],
+    groups: &[Vec<usize>],
+    trailing_comments: &[String],
+) {
+    let mirrors = shroud
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, layer)| match layer.mirror_index_option {
+            Some(mirror_idx) if idx < mirror_idx => Some(format!("{{{idx},{mirror_idx}}}")),
+            _ => None,
+        })
+        .collect::<String>();
+    writeln!(s, "mirrors={{{mirrors}}}").unwrap();
+    let groups = groups
+        .iter()
+        .map(|group| {
+            format!(
+                "{{{}}}",
+                group
+                    .iter()
+                    .map(|idx| idx.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        })
+        .collect::<String>();
+    writeln!(s, "groups={{{groups}}}").unwrap();
+    writeln!(s, "shapes={}", to_long_string(shapes_text)).unwrap();
+    writeln!(s, "shroud={{").unwrap();
+    shroud.iter().for_each(|layer| {
+        layer
+            .comments
+            .iter()
+            .for_each(|comment| writeln!(s, "{comment}").unwrap());
+        writeln!(s, "\t{}", format_shroud_layer_exact(layer)).unwrap();
+    });
+    trailing_comments
+        .iter()
+        .for_each(|comment| writeln!(s, "{comment}").unwrap());
+    writeln!(s, "}}").unwrap();
+}
+
+/// Writes a table back out as it was read.
+pub fn format_table(table: &Table) -> String {
+    let mut s = String::from("{");
+    table.iter().for_each(|(name, values)| match &values[..] {
+        [value] => write!(s, " {name}={value}").unwrap(),
+        values => write!(s, " {name}={{{}}}", values.join(",")).unwrap(),
+    });
+    s.push_str(" }");
+    s
+}
+
+pub fn is_project_text(s: &str) -> bool {
+    s.trim_start().starts_with(PROJECT_FILE_HEADER)
+}
+
+/// Unlike the export path, this writes every field at full precision and without the block offset
+/// removed, so a project file reloads into exactly the same editor state. Fields the editor did not
+/// understand go last, as they were written.
+pub fn format_shroud_layer_exact(shroud_layer_container: &ShroudLayerContainer) -> String {
+    let shroud_layer = &shroud_layer_container.shroud_layer;
+    let mut s = String::from("{");
+    if let Some(color_1) = &shroud_layer.color_1 {
+        write!(s, " tri_color_id={}", shroud_layer_color_id(color_1)).unwrap();
+    }
+    if let Some(color_2) = &shroud_layer.color_2 {
+        write!(s, " tri_color1_id={}", shroud_layer_color_id(color_2)).unwrap();
+    }
+    if let Some(line_color) = &shroud_layer.line_color {
+        write!(s, " line_color_id={}", shroud_layer_color_id(line_color)).unwrap();
+    }
+    if let Some(shape) = &shroud_layer.shape {
+        write!(s, " shape={shape}").unwrap();
+    }
+    if let Some(size) = &shroud_layer.size {
+        write!(s, " size={{{},{}}}", size.x.to_f32(), size.y.to_f32()).unwrap();
+    }
+    if let Some(offset) = &shroud_layer.offset {
+        write!(
+            s,
+            " offset={{{},{},{}}}",
+            offset.x.to_f32(),
+            offset.y.to_f32(),
+            offset.z.to_f32()
+        )
+        .unwrap();
+    }
+    if let Some(angle) = &shroud_layer.angle {
+        write!(s, " angle={}", angle.as_radians().get_value()).unwrap();
+    }
+    if let Some(taper) = shroud_layer.taper {
+        write!(s, " taper={taper}").unwrap();
+    }
+    shroud_layer_container
+        .unknown_fields
+        .iter()
+        .for_each(|(name, value)| write!(s, " {name}={value}").unwrap());
+    s.push_str(" }");
+    s
+}
+
+pub fn table_str<'a>(table: &Table<'a>, name: &str) -> Option<&'a str> {
+    table
+        .iter()
+        .find(|(variable_name, _)| *variable_name == name)
+        .and_then(|(_, values)| values.first().copied())
+}
+
+pub fn table_f32(table: &Table, name: &str) -> Option<f32> {
+    table_str(table, name).and_then(|value| value.parse().ok())
+}
+
+pub fn table_bool(table: &Table, name: &str) -> Option<bool> {
+    table_str(table, name).and_then(|value| value.parse().ok())
+}
+
+pub fn table_pos2(table: &Table, name: &str) -> Option<Pos2> {
+    let (_, values) = table
+        .iter()
+        .find(|(variable_name, _)| *variable_name == name)?;
+    Some(pos2(
+        values.first()?.parse().ok()?,
+        values.get(1)?.parse().ok()?,
+    ))
+}
+
+fn project(input: &str) -> IResult<&str, ProjectSections<'_>> {
+    let (remainder, _) = (multispace0, tag(PROJECT_FILE_HEADER)).parse(input)?;
+    let (remainder, _) = (ws, tag("version"), ws_and_equals).parse(remainder)?;
+    let (remainder, version) = map_res(digit1, str::parse::<u32>).parse(remainder)?;
+    let (remainder, editor) = section("editor", table).parse(remainder)?;
+    let (remainder, block) = section("block", table).parse(remainder)?;
+    let (remainder, reference_image) = section("reference_image", table).parse(remainder)?;
+    let (remainder, reference_image_path_option) =
+        opt(section("reference_image_path", long_string)).parse(remainder)?;
+    let (remainder, mirrors) = section("mirrors", index_lists).parse(remainder)?;
+    let (remainder, groups) = section("groups", index_lists).parse(remainder)?;
+    let (remainder, shapes) = section("shapes", long_string).parse(remainder)?;
+    let (remainder, _) = ws(remainder)?;
+    Ok((
+        "",
+        ProjectSections {
+            version,
+            editor,
+            block,
+            reference_image,
+            reference_image_path_option,
+            mirrors,
+            groups,
+            shapes,
+            shroud: remainder,
+        },
+    ))
+}
+
+/// A Lua long string, `[[...]]`, `[=[...]=]` and so on.
+fn long_string(input: &str) -> IResult<&str, &str> {
+    let (remainder, level) =
+        delimited(tag("["), take_while(|c| c == '='), tag("[")).parse(input)?;
+    let closing = format!("]{level}]");
+    let (remainder, content) = take_until(closing.as_str()).parse(remainder)?;
+    let (remainder, _) = tag(closing.as_str()).parse(remainder)?;
+    Ok((remainder, content))
+}
+
+/// Wraps `content` in the shortest long brackets that it cannot end early.
+pub fn to_long_string(content: &str) -> String {
+    let level = (0..)
+        .map(|level| "=".repeat(level))
+        .find(|level| !format!("{content}]").contains(&format!("]{level}]")))
+        .unwrap();
+    format!("[{level}[{content}]{level}]")
+}
+
+fn section<'a, O, F>(
+    name: &'a str,
+    inner: F,
+) -> impl Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>
+where
+    F: Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>,
+{
+    preceded((ws, tag(name), ws_and_equals), inner)
+}
+
+/// Like everything in the project file, a table may have whitespace just inside its braces, even
+/// when it is empty.
+fn table(input: &str) -> IResult<&str, Table<'_>> {
+    brackets_around(ws_around(many0(ws_around((
+        variable_name,
+        preceded(
+            ws_and_equals,
+            alt((
+                brackets_around(many1(ws_around(table_token))),
+                map(table_token, |token| vec![token]),
+            )),
+        ),
+    )))))
+    .parse(input)
+}
+
+fn table_token(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| !c.is_whitespace() && !['{', '}', ','].contains(&c)).parse(input)
+}
+
+fn index_lists(input: &str) -> IResult<&str, Vec<Vec<usize>>> {
+    brackets_around(ws_around(many0(ws_around(brackets_around(many1(
+        ws_around(map_res(digit1, str::parse::<usize>)),
+    ))))))
+    .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_text(mirrors: &str, groups: &str) -> String {
+        format!(
+            "{}editor={{ }}\nblock={{ offset={{1,2}} use_non_turreted_offset=false }}\nreference_image={{}}\nmirrors={mirrors}\ngroups={groups}\nshapes=[[]]\nshroud={{\n\t{{ offset={{0,1,0}} }}\n\t{{ offset={{0,-1,0}} }}\n\t{{ offset={{2,0,0}} }}\n}}\n",
+            project_header()
+        )
+    }
+
+    #[test]
+    fn parses_sections() {
+        let text = project_text("{{0,1}}", "{ {1,2} }");
+        let sections = parse_project(&text).unwrap();
+        assert_eq!(sections.version, PROJECT_FILE_VERSION);
+        assert!(sections.editor.is_empty());
+        assert_eq!(table_pos2(&sections.block, "offset"), Some(pos2(1.0, 2.0)));
+        assert_eq!(
+            table_bool(&sections.block, "use_non_turreted_offset"),
+            Some(false)
+        );
+        assert_eq!(sections.mirrors, vec![vec![0, 1]]);
+        assert_eq!(sections.groups, vec![vec![1, 2]]);
+        assert!(sections.shroud.starts_with("shroud={"));
+        let (shroud, _) = parse_project_shroud(&sections, &[]).unwrap();
+        assert_eq!(shroud[0].mirror_index_option, Some(1));
+        assert_eq!(shroud[1].mirror_index_option, Some(0));
+        assert_eq!(shroud[2].group_idx_option, Some(0));
+    }
+
+    #[test]
+    fn requires_header() {
+        let text = project_text("{}", "{}");
+        let without_header = text.strip_prefix(PROJECT_FILE_HEADER).unwrap();
+        assert!(matches!(
+            parse_project(without_header),
+            Err(ProjectFileMessage::NotAProject)
+        ));
+        let commented = format!("--some other file\n{without_header}");
+        assert!(matches!(
+            parse_project(&commented),
+            Err(ProjectFileMessage::NotAProject)
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_links() {
+        let shroud_result = |mirrors: &str, groups: &str| {
+            let text = project_text(mirrors, groups);
+            parse_project_shroud(&parse_project(&text).unwrap(), &[]).map(|_| ())
+        };
+        assert!(matches!(
+            shroud_result("{{0,1,2}}", "{}"),
+            Err(ProjectFileMessage::MirrorPair(0))
+        ));
+        assert!(matches!(
+            shroud_result("{{2,2}}", "{}"),
+            Err(ProjectFileMessage::LayerLinks(LayerLinkError::SelfMirror(
+                2
+            )))
+        ));
+        assert!(matches!(
+            shroud_result("{{0,1}{1,2}}", "{}"),
+            Err(ProjectFileMessage::LayerLinks(
+                LayerLinkError::ConflictingMirror(1, 0, 2)
+            ))
+        ));
+        assert!(matches!(
+            shroud_result("{}", "{{0,1}{1,2}}"),
+            Err(ProjectFileMessage::LayerLinks(
+                LayerLinkError::GroupMembership(1, 0, 1)
+            ))
+        ));
+        assert!(matches!(
+            shroud_result("{{0,3}}", "{}"),
+            Err(ProjectFileMessage::LayerLinks(LayerLinkError::MirrorIndex(
+                0, 3
+            )))
+        ));
+    }
+
+    #[test]
+    fn exact_format_keeps_unknown_fields() {
+        let (shroud, _) =
+            parse_shroud_text("shroud={ {offset={1,2,0.5} foo=\"bar\" baz={1,2}} }", &[]).unwrap();
+        let formatted = format_shroud_layer_exact(&shroud[0]);
+        assert!(formatted.ends_with(" foo=\"bar\" baz={1,2} }"));
+        let (reparsed, _) = parse_shroud_text(&format!("shroud={{{formatted}}}"), &[]).unwrap();
+        assert_eq!(reparsed[0].unknown_fields, shroud[0].unknown_fields);
+        let offset = reparsed[0].shroud_layer.offset.as_ref().unwrap();
+        assert_eq!(
+            (offset.x.to_f32(), offset.y.to_f32(), offset.z.to_f32()),
+            (1.0, 2.0, 0.5)
+        );
+    }
+
+    #[test]
+    fn long_string_level_is_not_closed_early() {
+        assert_eq!(to_long_string("a"), "[[a]]");
+        assert_eq!(to_long_string("a]]"), "[=[a]]]=]");
+        assert_eq!(to_long_string("a]"), "[=[a]]=]");
+        let s = to_long_string("]=]]]");
+        assert_eq!(long_string(&s), Ok(("", "]=]]]")));
+    }
+}
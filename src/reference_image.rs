@@ -16,9 +16,10 @@ use crate::{
 pub struct ReferenceImage {
     image_option: Option<SizedTexture>,
     handle_option: Option<TextureHandle>,
-    pos: Pos2,
-    scale: f32,
-    opacity: f32,
+    pub pos: Pos2,
+    pub scale: f32,
+    pub opacity: f32,
+    pub path_option: Option<PathBuf>,
     pub image_layer: ImageLayer,
     pub enabled: bool,
 }
@@ -37,6 +38,7 @@ impl Default for ReferenceImage {
             pos: Pos2::ZERO,
             scale: 1.0,
             opacity: 1.0,
+            path_option: None,
             image_layer: ImageLayer::ImageBelow,
             enabled: true,
         }
@@ -108,6 +110,7 @@ impl ShroudEditor {
             let color_image =
                 ColorImage::from_rgba_premultiplied([image.width, image.height], &image.bytes);
             self.load_image(ctx, color_image);
+            self.reference_image.path_option = None;
         }
     }
 
@@ -126,8 +129,9 @@ impl ShroudEditor {
     }
 
     pub fn import_reference_image_from_file(&mut self, ctx: &Context, path: PathBuf) {
-        if let Ok(color_image) = self.try_import_reference_image_from_path(path) {
+        if let Ok(color_image) = self.try_import_reference_image_from_path(path.clone()) {
             self.load_image(ctx, color_image);
+            self.reference_image.path_option = Some(path);
         }
     }
 
@@ -1,4 +1,4 @@
-use egui::Pos2;
+use emath::Pos2;
 use luexks_reassembly::shapes::vertices::Vertices;
 
 pub fn restructure_vertices(vertices: Vertices) -> Vec<Pos2> {
@@ -1,9 +1,11 @@
 use luexks_reassembly::shapes::{shape::Shape, shapes::Shapes};
+use parse_vanilla_shapes::VANILLA_SHAPE_COUNT;
 
 #[derive(Clone)]
 pub struct ShapeContainer {
     pub s: Shape,
     pub invert_height_of_mirror: bool,
+    pub scale_ports: Vec<Option<String>>,
 }
 
 impl ShapeContainer {
@@ -11,6 +13,7 @@ impl ShapeContainer {
         ShapeContainer {
             s: shape,
             invert_height_of_mirror: false,
+            scale_ports: Vec::new(),
         }
     }
 }
@@ -18,3 +21,21 @@ impl ShapeContainer {
 pub fn restructure_shapes(shapes: Shapes) -> Vec<ShapeContainer> {
     shapes.0.into_iter().map(ShapeContainer::new).collect()
 }
+
+/// The vanilla shapes at the start of `loaded_shapes` followed by `imported_shapes`, with the
+/// shapes at `non_mirrors` marked as not being mirrors.
+pub fn with_imported_shapes(
+    loaded_shapes: &[ShapeContainer],
+    imported_shapes: Vec<ShapeContainer>,
+    non_mirrors: &[usize],
+) -> Vec<ShapeContainer> {
+    let mut shapes = loaded_shapes[0..VANILLA_SHAPE_COUNT]
+        .iter()
+        .cloned()
+        .chain(imported_shapes)
+        .collect::<Vec<_>>();
+    non_mirrors.iter().for_each(|non_mirror| {
+        shapes[*non_mirror].set_invert_height_of_mirror();
+    });
+    shapes
+}
@@ -1,17 +1,31 @@
 use std::f32;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crate::block_container::BlockContainer;
+use crate::export_format::ExportFormat;
 use crate::file_import_export::WhichFileDialog;
-use crate::keybind_deserialiser::try_load_keybinds;
+use crate::json_interchange::JsonMessage;
+use crate::keybind_deserialiser::load_keybinds;
 use crate::keybinds::Keybinds;
 use crate::mirror_pairs::get_loaded_shapes_mirror_pairs;
+use crate::parse_shapes_text::ShapesMessage;
+use crate::parse_shroud_text::ShroudParseResult;
+use crate::project_format::ProjectFileMessage;
 use crate::reference_image::ReferenceImage;
 use crate::shape_container::{ShapeContainer, restructure_shapes};
 use crate::shapes_import_text_default::SHAPES_IMPORT_TEXT_DEFAULT;
-use crate::shroud_editor::parse_shapes_text::ShapesMessage;
-use crate::shroud_editor::parse_shroud_text::ShroudParseResult;
+use crate::shroud_editor::blocks_file::BlocksFile;
+use crate::shroud_editor::documents::Document;
+use crate::shroud_editor::export_presets::ExportPresets;
+use crate::shroud_editor::hot_reload::HotReload;
+use crate::shroud_editor::live_link::{LiveLink, LiveLinkTarget};
+use crate::shroud_editor::mod_folder::{ModFolder, ModFolderMessage};
+use crate::shroud_editor::parse_blocks_text::BlocksMessage;
+use crate::shroud_editor::paste_box::PasteBoxError;
+use crate::shroud_editor::preferences::RecentFiles;
+use crate::shroud_editor::recovery::Recovery;
 use crate::shroud_editor::render_shroud::RenderData;
 use crate::shroud_editor::shroud_layer_reordering::ShroudLayerReorderingMessageData;
 use crate::shroud_editor::tools::ToolSettings;
@@ -33,6 +47,7 @@ pub const DRAG_VALUE_MAX: f32 = f32::MAX;
 pub struct ShroudEditor {
     pub block_container: BlockContainer,
     pub shroud: Vec<ShroudLayerContainer>,
+    pub shroud_trailing_comments: Vec<String>,
     pub shroud_interaction: ShroudInteraction,
     pub zoom: f32,
     pub settings_grid_size: f32,
@@ -41,12 +56,16 @@ pub struct ShroudEditor {
     pub grid_snap_enabled: bool,
     angle_snap: f32,
     angle_snap_enabled: bool,
+    object_snap_enabled: bool,
+    object_snap_distance: f32,
+    object_snap_pos_option: Option<Pos2>,
     pub pan: Pos2,
     // key_tracker: KeyTracker,
     loaded_shapes: Vec<ShapeContainer>,
     just_exported_to_clipboard_success_option: Option<bool>,
     just_exported_to_file_next_to_exe_status: Option<bool>,
     just_exported_to_file_status: Option<bool>,
+    just_exported_shapes_to_file_status: Option<bool>,
     pub fill_color_gradient: f32,
     fill_color_gradient_increasing: bool,
     fill_color_gradient_delta_enabled: bool,
@@ -55,20 +74,33 @@ pub struct ShroudEditor {
     only_show_selected_shroud_layers: bool,
     pub(crate) world_mouse_pos: Pos2,
     shroud_clipboard: Vec<ShroudLayerContainer>,
+    /// What copying last put on the system clipboard, to tell if something else has been copied since.
+    shroud_clipboard_text: String,
     loaded_shapes_mirror_pairs: Vec<(usize, usize)>,
+    loaded_shapes_text: String,
     shroud_import_text: String,
     just_imported_shroud_from_paste_box_message_option: Option<ShroudParseResult>,
+    just_imported_shroud_from_file_message_option: Option<ShroudParseResult>,
+    shroud_paste_box_error_option: Option<PasteBoxError>,
+    pub shroud_import_path_option: Option<PathBuf>,
+    pub link_mirrors_on_import: bool,
+    pub lenient_shroud_import: bool,
+    pub append_shroud_import: bool,
+    pub shroud_import_problems: Vec<ShroudParseResult>,
     shape_search_buf: String,
     shape_search_show_vanilla: bool,
     shapes_import_text: String,
     just_imported_shapes_from_paste_box_message_option: Option<ShapesMessage>,
     just_imported_shapes_from_file_message_option: Option<ShapesMessage>,
+    shapes_paste_box_error_option: Option<PasteBoxError>,
+    pub shapes_import_path_option: Option<PathBuf>,
     tool_settings: ToolSettings,
     shroud_layer_reordering_message_data_option: Option<ShroudLayerReorderingMessageData>,
     float_shroud_settings: bool,
     render_data_option: Arc<Mutex<Option<RenderData>>>,
     pub(crate) visual_panel_key_bindings_enabled: bool,
     pub keybinds: Keybinds,
+    pub keybinds_unparsed_lines: Vec<String>,
     pub undo_history: Vec<UndoHistorySnapshot>,
     pub add_undo_history: bool,
     pub undo_history_index: usize,
@@ -83,15 +115,36 @@ pub struct ShroudEditor {
     pub outline_groups: bool,
     pub(crate) show_right_click_shroud_add: bool,
     right_click_shroud_screen_pos: Pos2,
+    pub project_path_option: Option<PathBuf>,
+    pub unsaved_changes: bool,
+    project_file_message_option: Option<ProjectFileMessage>,
+    blocks_file_option: Option<BlocksFile>,
+    blocks_file_message_option: Option<BlocksMessage>,
+    mod_folder_option: Option<ModFolder>,
+    mod_folder_message_option: Option<ModFolderMessage>,
+    hot_reload: HotReload,
+    live_link_target_option: Option<LiveLinkTarget>,
+    live_link: LiveLink,
+    recovery: Recovery,
+    recent_files: RecentFiles,
+    pub export_format: ExportFormat,
+    export_presets: ExportPresets,
+    export_preset_name: String,
+    json_file_message_option: Option<JsonMessage>,
+    documents: Vec<Document>,
+    active_document_idx: usize,
+    close_document_prompt_option: Option<usize>,
 }
 
 impl Default for ShroudEditor {
     fn default() -> Self {
         let loaded_shapes = restructure_shapes(get_vanilla_shapes());
         let loaded_shapes_mirror_pairs = get_loaded_shapes_mirror_pairs(&loaded_shapes);
+        let (keybinds, keybinds_unparsed_lines) = load_keybinds();
         Self {
             block_container: Default::default(),
             shroud: Vec::default(),
+            shroud_trailing_comments: Vec::new(),
             shroud_interaction: ShroudInteraction::Inaction {
                 selection: Vec::new(),
             },
@@ -102,12 +155,16 @@ impl Default for ShroudEditor {
             grid_snap_enabled: true,
             angle_snap: 5.0,
             angle_snap_enabled: true,
+            object_snap_enabled: true,
+            object_snap_distance: 8.0,
+            object_snap_pos_option: None,
             pan: Pos2::new(0.0, 0.0),
             // key_tracker: KeyTracker::default(),
             loaded_shapes,
             just_exported_to_clipboard_success_option: None,
             just_exported_to_file_next_to_exe_status: None,
             just_exported_to_file_status: None,
+            just_exported_shapes_to_file_status: None,
             fill_color_gradient: 0.0,
             fill_color_gradient_increasing: true,
             fill_color_gradient_delta_enabled: true,
@@ -116,20 +173,32 @@ impl Default for ShroudEditor {
             only_show_selected_shroud_layers: true,
             world_mouse_pos: Pos2::default(),
             shroud_clipboard: Vec::new(),
+            shroud_clipboard_text: String::new(),
             loaded_shapes_mirror_pairs,
+            loaded_shapes_text: String::new(),
             shroud_import_text: SHROUD_IMPORT_TEXT_DEFAULT.to_string(),
             just_imported_shroud_from_paste_box_message_option: None,
+            just_imported_shroud_from_file_message_option: None,
+            shroud_paste_box_error_option: None,
+            shroud_import_path_option: None,
+            link_mirrors_on_import: true,
+            lenient_shroud_import: false,
+            append_shroud_import: false,
+            shroud_import_problems: Vec::new(),
             shape_search_buf: String::new(),
             shape_search_show_vanilla: true,
             shapes_import_text: SHAPES_IMPORT_TEXT_DEFAULT.to_string(),
             just_imported_shapes_from_paste_box_message_option: None,
             just_imported_shapes_from_file_message_option: None,
+            shapes_paste_box_error_option: None,
+            shapes_import_path_option: None,
             tool_settings: ToolSettings::default(),
             shroud_layer_reordering_message_data_option: None,
             float_shroud_settings: false,
             render_data_option: Arc::new(Mutex::new(None)),
             visual_panel_key_bindings_enabled: true,
-            keybinds: try_load_keybinds().unwrap_or_default(),
+            keybinds,
+            keybinds_unparsed_lines,
             undo_history: [UndoHistorySnapshot::default()].into(),
             add_undo_history: false,
             undo_history_index: 0,
@@ -144,6 +213,25 @@ impl Default for ShroudEditor {
             outline_groups: true,
             show_right_click_shroud_add: false,
             right_click_shroud_screen_pos: Default::default(),
+            project_path_option: None,
+            unsaved_changes: false,
+            project_file_message_option: None,
+            blocks_file_option: None,
+            blocks_file_message_option: None,
+            mod_folder_option: None,
+            mod_folder_message_option: None,
+            hot_reload: HotReload::default(),
+            live_link_target_option: None,
+            live_link: LiveLink::default(),
+            recovery: Recovery::default(),
+            recent_files: Vec::new(),
+            export_format: ExportFormat::default(),
+            export_presets: Vec::new(),
+            export_preset_name: String::new(),
+            json_file_message_option: None,
+            documents: vec![Document::default()],
+            active_document_idx: 0,
+            close_document_prompt_option: None,
         }
     }
 }
@@ -173,6 +261,7 @@ impl eframe::App for ShroudEditor {
 
         self.fill_color_gradient_delta();
         self.left_panel(ctx);
+        self.document_tabs(ctx);
 
         self.file_dialog_visual_panel_key_bindings_enabled_logic();
 
@@ -198,27 +287,44 @@ impl eframe::App for ShroudEditor {
         self.add_undo_history_logic();
 
         self.file_import_logic(ctx);
+        self.hot_reload_logic(ctx);
+
+        self.recovery_prompt(ctx);
+        self.close_document_prompt(ctx);
+        self.autosave_logic(ctx);
+        self.preferences_logic(ctx);
 
         ctx.request_repaint_after(Duration::from_secs_f32(1.0 / 60.0));
     }
 }
 
-mod add_mirror;
+mod blocks_file;
 mod bulk_set;
 mod delete_shroud_layers;
 mod delta_time;
+mod documents;
 mod draw_grid;
+mod export_presets;
+mod export_shapes;
 mod export_shroud;
 mod grouping;
 mod half_grid_size_key_logic;
+mod hot_reload;
 mod hotkey_copy_and_paste;
 mod hotkey_mirroring;
 mod hotkey_shroud_layer_deletion;
 mod import_shapes;
+mod import_shroud;
+mod json_file;
 mod left_panel;
-mod parse_shapes_text;
-mod parse_shroud_text;
-mod parsing;
+pub mod live_link;
+mod mod_folder;
+mod object_snap;
+mod parse_blocks_text;
+mod paste_box;
+mod preferences;
+pub mod project_file;
+mod recovery;
 mod render_polygon;
 mod render_shroud;
 mod right_click_shroud_add;
@@ -0,0 +1,271 @@
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use egui::{Grid, ScrollArea, Ui, collapsing_header::CollapsingState};
+use luexks_reassembly::blocks::shroud_layer::ShroudLayerColor;
+
+use crate::{
+    block_container::BlockContainer,
+    color_type_conversion::str_to_rgba_option,
+    file_import_export::{WhichFileDialog, write_file_atomically},
+    shroud_editor::{
+        ShroudEditor,
+        live_link::LiveLinkTarget,
+        parse_blocks_text::{BlockEntry, BlocksMessage, parse_blocks_text, replace_block_shroud},
+        preferences::RecentFileKind,
+    },
+    shroud_interaction::ShroudInteraction,
+};
+
+pub struct BlocksFile {
+    pub path: PathBuf,
+    pub text: String,
+    pub blocks: Vec<BlockEntry>,
+    pub loaded_block_idx_option: Option<usize>,
+}
+
+impl ShroudEditor {
+    pub fn blocks_file_settings(&mut self, ui: &mut Ui) {
+        CollapsingState::load_with_default_open(ui.ctx(), "blocks_file".into(), false)
+            .show_header(ui, |ui| {
+                ui.strong("Edit Shroud in blocks.lua");
+            })
+            .body(|ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Open blocks.lua").clicked() {
+                        self.which_file_import = WhichFileDialog::BlocksOpen;
+                        self.file_dialog.pick_file();
+                    }
+                    if let Some(blocks_file) = &self.blocks_file_option
+                        && let Some(loaded_block_idx) = blocks_file.loaded_block_idx_option
+                        && ui
+                            .button(format!(
+                                "Write Shroud to {}",
+                                blocks_file.blocks[loaded_block_idx].label()
+                            ))
+                            .clicked()
+                    {
+                        self.write_shroud_to_blocks_file();
+                    }
+                });
+                if let Some(message) = &self.blocks_file_message_option {
+                    ui.label(message.to_string());
+                }
+                let Some(blocks_file) = &self.blocks_file_option else {
+                    return;
+                };
+                ui.small(blocks_file.path.display().to_string());
+                let mut block_idx_to_load_option = None;
+                ScrollArea::vertical()
+                    .id_salt("blocks_file_blocks")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        Grid::new("blocks_file_grid").striped(true).show(ui, |ui| {
+                            for (block_idx, block) in blocks_file.blocks.iter().enumerate() {
+                                let is_loaded =
+                                    blocks_file.loaded_block_idx_option == Some(block_idx);
+                                let response = ui.selectable_label(is_loaded, block.label());
+                                if response.clicked() {
+                                    block_idx_to_load_option = Some((block_idx, false));
+                                }
+                                response.context_menu(|ui| {
+                                    if ui.button("Open in New Tab").clicked() {
+                                        block_idx_to_load_option = Some((block_idx, true));
+                                    }
+                                });
+                                ui.label(block.shape.as_deref().unwrap_or("SQUARE"));
+                                ui.label(block.fill_color.as_deref().unwrap_or("-"));
+                                ui.label(block.fill_color_1.as_deref().unwrap_or("-"));
+                                ui.label(block.line_color.as_deref().unwrap_or("-"));
+                                ui.label(if block.shroud_span_option.is_some() {
+                                    "shroud"
+                                } else {
+                                    ""
+                                });
+                                ui.end_row();
+                            }
+                        });
+                    });
+                if let Some((block_idx, in_new_tab)) = block_idx_to_load_option {
+                    self.open_block_from_blocks_file(block_idx, in_new_tab);
+                }
+            });
+    }
+
+    pub fn open_blocks_file(&mut self, path: PathBuf) {
+        match read_blocks_file(path) {
+            Ok(blocks_file) => self.set_blocks_file(blocks_file),
+            Err(err) => self.blocks_file_message_option = Some(err),
+        }
+    }
+
+    pub fn set_blocks_file(&mut self, blocks_file: BlocksFile) {
+        self.add_recent_file(RecentFileKind::Blocks, blocks_file.path.clone());
+        self.forget_documents_blocks_file_blocks();
+        self.blocks_file_option = Some(blocks_file);
+        self.blocks_file_message_option = Some(BlocksMessage::Success);
+    }
+
+    pub fn open_block_from_blocks_file(&mut self, block_idx: usize, in_new_tab: bool) {
+        if in_new_tab {
+            self.new_document();
+        }
+        self.blocks_file_message_option = Some(
+            self.load_block_from_blocks_file(block_idx)
+                .err()
+                .unwrap_or(BlocksMessage::Success),
+        );
+    }
+
+    fn load_block_from_blocks_file(&mut self, block_idx: usize) -> Result<(), BlocksMessage> {
+        let Some(blocks_file) = &self.blocks_file_option else {
+            return Ok(());
+        };
+        let blocks_file_path = blocks_file.path.clone();
+        let block = blocks_file.blocks[block_idx].clone();
+        let shroud_text_option = block
+            .shroud_span_option
+            .clone()
+            .map(|shroud_span| blocks_file.text[shroud_span].to_string());
+
+        let block_container = self.block_container_from_block_entry(&block)?;
+        let shroud_import_option = shroud_text_option
+            .map(|shroud_text| {
+                self.parse_shroud_import(&shroud_text, block_container.offset)
+                    .map_err(|err| BlocksMessage::Shroud(err.to_string()))
+            })
+            .transpose()?;
+
+        self.block_container = block_container;
+        self.icon_radius_option = None;
+        if let Some(shroud_import) = shroud_import_option {
+            self.apply_shroud_import(shroud_import);
+        } else {
+            self.shroud.clear();
+            self.shroud_trailing_comments.clear();
+            self.shroud_import_problems.clear();
+            self.groups.clear();
+            self.shroud_interaction = ShroudInteraction::none();
+            self.add_undo_history = true;
+        }
+        if let Some(blocks_file) = &mut self.blocks_file_option {
+            blocks_file.loaded_block_idx_option = Some(block_idx);
+        }
+        self.unlink_live_link_on_load(|live_link_target| {
+            matches!(
+                live_link_target,
+                LiveLinkTarget::Block { path, ident, .. }
+                    if *path == blocks_file_path && block.ident.as_ref() == Some(ident)
+            )
+        });
+        Ok(())
+    }
+
+    /// The current block settings with the shape, scale and colours that `block` sets.
+    pub fn block_container_from_block_entry(
+        &self,
+        block: &BlockEntry,
+    ) -> Result<BlockContainer, BlocksMessage> {
+        let shape_id = block.shape.as_deref().unwrap_or("SQUARE");
+        let shape = self
+            .loaded_shapes
+            .iter()
+            .find(|shape| shape.s.get_id().unwrap().to_string() == shape_id)
+            .ok_or(BlocksMessage::Shape(shape_id.to_string()))?;
+        let scale = block
+            .scale
+            .as_deref()
+            .and_then(|scale| scale.parse::<usize>().ok())
+            .unwrap_or(1);
+        let mut block_container = self.block_container.clone();
+        block_container.set_shape(shape, scale);
+        for (color_option, color_id) in [
+            (&block.fill_color, ShroudLayerColor::Color1),
+            (&block.fill_color_1, ShroudLayerColor::Color2),
+            (&block.line_color, ShroudLayerColor::LineColor),
+        ] {
+            if let Some(rgba) = color_option.as_deref().and_then(str_to_rgba_option) {
+                block_container.set_color(color_id, rgba);
+            }
+        }
+        if block_container.use_non_turreted_offset {
+            block_container.update_non_turreted_offset();
+        }
+        Ok(block_container)
+    }
+
+    pub fn write_shroud_to_blocks_file(&mut self) {
+        let shroud_export = self.export_shroud();
+        let Some(blocks_file) = &mut self.blocks_file_option else {
+            return;
+        };
+        let Some(loaded_block_idx) = blocks_file.loaded_block_idx_option else {
+            return;
+        };
+        let block = &blocks_file.blocks[loaded_block_idx];
+        let label = block.label();
+        let Some(ident) = block.ident.clone() else {
+            self.blocks_file_message_option = Some(BlocksMessage::NoIdent(label));
+            return;
+        };
+        self.blocks_file_message_option = Some(
+            write_block_shroud(blocks_file, &ident, &shroud_export)
+                .err()
+                .unwrap_or(BlocksMessage::Written(label)),
+        );
+    }
+}
+
+pub fn read_blocks_file(path: PathBuf) -> Result<BlocksFile, BlocksMessage> {
+    let text = read_to_string(&path).map_err(|_| BlocksMessage::CouldNotOpenFile)?;
+    let blocks = parse_blocks_text(&text)?;
+    Ok(BlocksFile {
+        path,
+        text,
+        blocks,
+        loaded_block_idx_option: None,
+    })
+}
+
+/// Rereads the file first so edits made elsewhere since opening it are kept.
+fn write_block_shroud(
+    blocks_file: &mut BlocksFile,
+    ident: &str,
+    shroud_export: &str,
+) -> Result<(), BlocksMessage> {
+    let (text, blocks, block_idx) =
+        write_block_shroud_to_file(&blocks_file.path, ident, shroud_export)?;
+    blocks_file.blocks = blocks;
+    blocks_file.text = text;
+    blocks_file.loaded_block_idx_option = Some(block_idx);
+    Ok(())
+}
+
+/// Replaces the shroud of the block with `ident` in the file as it is now. Nothing is written if
+/// no block or more than one block has that ident. Returns the new text, its blocks and the index
+/// of the block that was written to.
+pub fn write_block_shroud_to_file(
+    path: &Path,
+    ident: &str,
+    shroud_export: &str,
+) -> Result<(String, Vec<BlockEntry>, usize), BlocksMessage> {
+    let text = read_to_string(path).map_err(|_| BlocksMessage::CouldNotOpenFile)?;
+    let blocks = parse_blocks_text(&text)?;
+    let mut matching_block_idxs = blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block)| block.ident.as_deref() == Some(ident))
+        .map(|(block_idx, _)| block_idx);
+    let block_idx = matching_block_idxs
+        .next()
+        .ok_or(BlocksMessage::BlockNotFound(ident.to_string()))?;
+    if matching_block_idxs.next().is_some() {
+        return Err(BlocksMessage::DuplicateIdent(ident.to_string()));
+    }
+    let new_text = replace_block_shroud(&text, &blocks[block_idx], shroud_export);
+    write_file_atomically(path, &new_text).map_err(|_| BlocksMessage::CouldNotWriteFile)?;
+    let blocks = parse_blocks_text(&new_text)?;
+    Ok((new_text, blocks, block_idx))
+}
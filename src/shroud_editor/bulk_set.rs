@@ -2,12 +2,12 @@ use egui::{Color32, Stroke, Ui};
 use luexks_reassembly::blocks::shroud_layer::{ShroudLayer, ShroudLayerColor};
 
 use crate::{
+    add_mirror::get_mirrored_shape_data,
     restructure_vertices::restructure_vertices,
     right_tri_angle_edge_case::{RIGHT_TRI, rotate_right_tri_shroud_layer_mirror},
     shape_container::ShapeContainer,
     shroud_editor::{
         ShroudEditor,
-        add_mirror::get_mirrored_shape_data,
         shape_combo_box::shroud_layer_shape_combo_box,
         shroud_settings::{
             ShroudLayerSettingsTarget, colour_settings, full_angle_settings, offset_settings,
@@ -49,6 +49,7 @@ impl ShroudLayerSettingsTarget for BulkSettingsTarget<'_> {
         self.selection.clone().into_iter().for_each(|idx| {
             self.shroud[idx].shape_id = shape.s.get_id().unwrap().to_string();
             self.shroud[idx].vertices = restructure_vertices(shape.s.get_first_scale_vertices());
+            self.shroud[idx].shape_is_missing = false;
             self.shroud[idx].invert_height_of_mirror = shape.invert_height_of_mirror;
             self.shroud[idx].shroud_layer.shape = shape.s.get_id();
             if let Some(mirror_idx) = self.shroud[idx].mirror_index_option {
@@ -33,7 +33,16 @@ impl ShroudEditor {
             .rev()
             .collect::<Vec<_>>();
         to_be_deleted_indexes.iter().for_each(|layer_idx| {
-            self.shroud.remove(*layer_idx);
+            let comments = self.shroud.remove(*layer_idx).comments;
+            // Comments mark positions in the shroud, so they move onto whatever comes next.
+            match self.shroud.get_mut(*layer_idx) {
+                Some(next_layer) => {
+                    next_layer.comments.splice(0..0, comments);
+                }
+                None => {
+                    self.shroud_trailing_comments.splice(0..0, comments);
+                }
+            }
             self.mirror_idx_logic_for_deleted_layer_idx(*layer_idx);
             self.groups_logic_for_deleted_layer_idx(*layer_idx);
             self.selection_logic_for_deleted_layer_idx(*layer_idx);
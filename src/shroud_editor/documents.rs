@@ -0,0 +1,285 @@
+use std::{
+    mem::{swap, take},
+    path::PathBuf,
+};
+
+use egui::{Button, Context, Id, Modal, Pos2};
+
+use crate::{
+    block_container::BlockContainer,
+    parse_shroud_text::ShroudParseResult,
+    reference_image::ReferenceImage,
+    shroud_editor::{ShroudEditor, live_link::LiveLinkTarget},
+    shroud_interaction::ShroudInteraction,
+    shroud_layer_container::ShroudLayerContainer,
+    undo_redo::UndoHistorySnapshot,
+};
+
+/// Everything that belongs to one tab. The active tab's copy lives in the editor's own fields,
+/// so its slot in `ShroudEditor::documents` is left stale until another tab is switched to.
+pub struct Document {
+    block_container: BlockContainer,
+    shroud: Vec<ShroudLayerContainer>,
+    shroud_trailing_comments: Vec<String>,
+    groups: Vec<Vec<usize>>,
+    undo_history: Vec<UndoHistorySnapshot>,
+    undo_history_index: usize,
+    shroud_interaction: ShroudInteraction,
+    pan: Pos2,
+    zoom: f32,
+    project_path_option: Option<PathBuf>,
+    shroud_import_path_option: Option<PathBuf>,
+    blocks_file_block_idx_option: Option<usize>,
+    live_link_target_option: Option<LiveLinkTarget>,
+    icon_radius_option: Option<f32>,
+    shroud_import_problems: Vec<ShroudParseResult>,
+    reference_image: ReferenceImage,
+    unsaved_changes: bool,
+    /// The tab's project text as of when it was switched away from, for recovery.
+    project: String,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self {
+            block_container: BlockContainer::default(),
+            shroud: Vec::new(),
+            shroud_trailing_comments: Vec::new(),
+            groups: Vec::new(),
+            undo_history: [UndoHistorySnapshot::default()].into(),
+            undo_history_index: 0,
+            shroud_interaction: ShroudInteraction::none(),
+            pan: Pos2::new(0.0, 0.0),
+            zoom: 1.0,
+            project_path_option: None,
+            shroud_import_path_option: None,
+            blocks_file_block_idx_option: None,
+            live_link_target_option: None,
+            icon_radius_option: None,
+            shroud_import_problems: Vec::new(),
+            reference_image: ReferenceImage::default(),
+            unsaved_changes: false,
+            project: String::new(),
+        }
+    }
+}
+
+impl Document {
+    fn title(&self, document_idx: usize) -> String {
+        document_title(
+            self.project_path_option
+                .as_ref()
+                .or(self.shroud_import_path_option.as_ref()),
+            document_idx,
+        )
+    }
+}
+
+fn document_title(path_option: Option<&PathBuf>, document_idx: usize) -> String {
+    path_option
+        .and_then(|path| path.file_name())
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_else(|| format!("Untitled {}", document_idx + 1))
+}
+
+impl ShroudEditor {
+    pub fn document_tabs(&mut self, ctx: &Context) {
+        egui::TopBottomPanel::top("document_tabs").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                let mut switch_to_option = None;
+                let mut close_option = None;
+                let can_close = self.documents.len() > 1;
+                for document_idx in 0..self.documents.len() {
+                    let is_active = document_idx == self.active_document_idx;
+                    let title = if is_active {
+                        self.active_document_title()
+                    } else {
+                        self.documents[document_idx].title(document_idx)
+                    };
+                    if ui.selectable_label(is_active, title).clicked() && !is_active {
+                        switch_to_option = Some(document_idx);
+                    }
+                    if ui
+                        .add_enabled(can_close, Button::new("×").small())
+                        .on_hover_text("Close tab")
+                        .clicked()
+                    {
+                        close_option = Some(document_idx);
+                    }
+                    ui.separator();
+                }
+                if ui.button("+").on_hover_text("New tab").clicked() {
+                    self.new_document();
+                }
+                if let Some(document_idx) = switch_to_option {
+                    self.switch_document(document_idx);
+                }
+                if let Some(document_idx) = close_option {
+                    if self.document_has_unsaved_changes(document_idx) {
+                        self.close_document_prompt_option = Some(document_idx);
+                    } else {
+                        self.close_document(document_idx);
+                    }
+                }
+            });
+        });
+    }
+
+    /// Asks before closing a tab with unsaved changes.
+    pub fn close_document_prompt(&mut self, ctx: &Context) {
+        let Some(document_idx) = self.close_document_prompt_option else {
+            return;
+        };
+        let title = if document_idx == self.active_document_idx {
+            self.active_document_title()
+        } else {
+            self.documents[document_idx].title(document_idx)
+        };
+        Modal::new(Id::new("close_document_prompt")).show(ctx, |ui| {
+            ui.heading("Close Tab");
+            ui.label(format!("{title} has unsaved changes. Close it anyway?"));
+            ui.horizontal(|ui| {
+                if ui.button("Close Without Saving").clicked() {
+                    self.close_document_prompt_option = None;
+                    self.close_document(document_idx);
+                }
+                if ui.button("Cancel").clicked() {
+                    self.close_document_prompt_option = None;
+                }
+            });
+        });
+    }
+
+    fn document_has_unsaved_changes(&self, document_idx: usize) -> bool {
+        if document_idx == self.active_document_idx {
+            self.unsaved_changes || self.add_undo_history
+        } else {
+            self.documents[document_idx].unsaved_changes
+        }
+    }
+
+    fn active_document_title(&self) -> String {
+        document_title(
+            self.project_path_option
+                .as_ref()
+                .or(self.shroud_import_path_option.as_ref()),
+            self.active_document_idx,
+        )
+    }
+
+    /// Opens an empty tab and switches to it. The block settings carry over from the current tab.
+    pub fn new_document(&mut self) {
+        self.documents.push(Document {
+            block_container: self.block_container.clone(),
+            ..Default::default()
+        });
+        self.switch_document(self.documents.len() - 1);
+    }
+
+    pub fn switch_document(&mut self, document_idx: usize) {
+        if document_idx == self.active_document_idx || document_idx >= self.documents.len() {
+            return;
+        }
+        self.add_undo_history_logic();
+        let project = self.serialise_project();
+        let mut documents = take(&mut self.documents);
+        documents[self.active_document_idx].project = project;
+        self.swap_active_document(&mut documents[self.active_document_idx]);
+        self.swap_active_document(&mut documents[document_idx]);
+        self.documents = documents;
+        self.active_document_idx = document_idx;
+        self.selection_box_start_pos_option = None;
+        self.show_right_click_shroud_add = false;
+        self.update_recovery_snapshot();
+    }
+
+    pub fn close_document(&mut self, document_idx: usize) {
+        if self.documents.len() <= 1 {
+            return;
+        }
+        if document_idx == self.active_document_idx {
+            let next_document_idx = if document_idx + 1 < self.documents.len() {
+                document_idx + 1
+            } else {
+                document_idx - 1
+            };
+            self.switch_document(next_document_idx);
+        }
+        self.documents.remove(document_idx);
+        if self.active_document_idx > document_idx {
+            self.active_document_idx -= 1;
+        }
+        self.update_recovery_snapshot();
+    }
+
+    fn swap_active_document(&mut self, document: &mut Document) {
+        swap(&mut self.block_container, &mut document.block_container);
+        swap(&mut self.shroud, &mut document.shroud);
+        swap(
+            &mut self.shroud_trailing_comments,
+            &mut document.shroud_trailing_comments,
+        );
+        swap(&mut self.groups, &mut document.groups);
+        swap(&mut self.undo_history, &mut document.undo_history);
+        swap(
+            &mut self.undo_history_index,
+            &mut document.undo_history_index,
+        );
+        swap(
+            &mut self.shroud_interaction,
+            &mut document.shroud_interaction,
+        );
+        swap(&mut self.pan, &mut document.pan);
+        swap(&mut self.zoom, &mut document.zoom);
+        swap(
+            &mut self.project_path_option,
+            &mut document.project_path_option,
+        );
+        swap(
+            &mut self.shroud_import_path_option,
+            &mut document.shroud_import_path_option,
+        );
+        swap(
+            &mut self.live_link_target_option,
+            &mut document.live_link_target_option,
+        );
+        swap(
+            &mut self.icon_radius_option,
+            &mut document.icon_radius_option,
+        );
+        swap(
+            &mut self.shroud_import_problems,
+            &mut document.shroud_import_problems,
+        );
+        swap(&mut self.reference_image, &mut document.reference_image);
+        swap(&mut self.unsaved_changes, &mut document.unsaved_changes);
+        if let Some(blocks_file) = &mut self.blocks_file_option {
+            swap(
+                &mut blocks_file.loaded_block_idx_option,
+                &mut document.blocks_file_block_idx_option,
+            );
+        }
+    }
+
+    /// The project text of each tab by index, for only the tabs with unsaved changes.
+    pub fn serialise_unsaved_documents(&self) -> Vec<Option<String>> {
+        (0..self.documents.len())
+            .map(|document_idx| {
+                if !self.document_has_unsaved_changes(document_idx) {
+                    None
+                } else if document_idx == self.active_document_idx {
+                    Some(self.serialise_project())
+                } else {
+                    Some(self.documents[document_idx].project.clone())
+                }
+            })
+            .collect()
+    }
+
+    /// The other tabs' loaded blocks refer to the blocks file that was open before.
+    pub fn forget_documents_blocks_file_blocks(&mut self) {
+        self.documents
+            .iter_mut()
+            .for_each(|document| document.blocks_file_block_idx_option = None);
+    }
+}
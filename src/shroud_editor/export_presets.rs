@@ -0,0 +1,211 @@
+use std::{fmt::Write as _, fs::read_to_string};
+
+use egui::{
+    Button, ComboBox, Context, DragValue, TextEdit, Ui, collapsing_header::CollapsingState,
+};
+
+use crate::{
+    config::{config_file_path, write_config_file},
+    export_format::{AngleFormat, ExportFormat, ExportLayout, Indentation},
+    shroud_editor::ShroudEditor,
+};
+
+const EXPORT_PRESETS_FILE_NAME: &str = "export_presets";
+const EXPORT_PRESETS_FILE_HEADER: &str = "--Luexks Shroud Editor export presets";
+const DECIMAL_PLACES_MAX: usize = 8;
+const INDENTATION_SPACES_MAX: usize = 8;
+
+pub type ExportPresets = Vec<(String, ExportFormat)>;
+
+/// Each preset is a `[name]` line followed by its settings as `key=value` lines.
+pub fn load_export_presets() -> ExportPresets {
+    let Ok(s) = read_to_string(config_file_path(EXPORT_PRESETS_FILE_NAME)) else {
+        return Vec::new();
+    };
+    let mut export_presets: ExportPresets = Vec::new();
+    for line in s.lines().map(str::trim) {
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            export_presets.push((name.to_string(), ExportFormat::default()));
+        } else if let Some((key, value)) = line.split_once('=')
+            && let Some((_, export_format)) = export_presets.last_mut()
+        {
+            export_format.set_key_value(key.trim(), value.trim());
+        }
+    }
+    export_presets
+}
+
+fn save_export_presets(export_presets: &ExportPresets) {
+    let mut s = String::new();
+    writeln!(s, "{EXPORT_PRESETS_FILE_HEADER}").unwrap();
+    export_presets.iter().for_each(|(name, export_format)| {
+        writeln!(s, "[{name}]").unwrap();
+        export_format
+            .key_values()
+            .into_iter()
+            .for_each(|(key, value)| writeln!(s, "{key}={value}").unwrap());
+    });
+    let _ = write_config_file(EXPORT_PRESETS_FILE_NAME, &s);
+}
+
+impl ShroudEditor {
+    pub fn export_format_settings(&mut self, ctx: &Context, ui: &mut Ui) {
+        CollapsingState::load_with_default_open(ctx, "export_format".into(), false)
+            .show_header(ui, |ui| ui.label("Export Format"))
+            .body_unindented(|ui| {
+                self.export_preset_buttons(ui);
+                let export_format = &mut self.export_format;
+
+                ui.horizontal(|ui| {
+                    let mut is_rounded = export_format.decimal_places_option.is_some();
+                    if ui.checkbox(&mut is_rounded, "Round to").changed() {
+                        export_format.decimal_places_option = is_rounded.then_some(3);
+                    }
+                    if let Some(decimal_places) = &mut export_format.decimal_places_option {
+                        ui.add(DragValue::new(decimal_places).range(0..=DECIMAL_PLACES_MAX));
+                        ui.label("decimal places");
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Layout:");
+                    ui.radio_value(
+                        &mut export_format.layout,
+                        ExportLayout::OneLayerPerLine,
+                        "One layer per line",
+                    );
+                    ui.radio_value(&mut export_format.layout, ExportLayout::Compact, "Compact");
+                });
+                if export_format.layout == ExportLayout::OneLayerPerLine {
+                    ui.horizontal(|ui| {
+                        ui.label("Indentation:");
+                        let is_tabs = export_format.indentation == Indentation::Tabs;
+                        if ui.radio(is_tabs, "Tab").clicked() {
+                            export_format.indentation = Indentation::Tabs;
+                        }
+                        if ui.radio(!is_tabs, "Spaces").clicked() && is_tabs {
+                            export_format.indentation = Indentation::Spaces(4);
+                        }
+                        if let Indentation::Spaces(count) = &mut export_format.indentation {
+                            ui.add(DragValue::new(count).range(0..=INDENTATION_SPACES_MAX));
+                        }
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Angles:");
+                    ui.radio_value(
+                        &mut export_format.angle_format,
+                        AngleFormat::Radians,
+                        "Radians",
+                    );
+                    ui.radio_value(
+                        &mut export_format.angle_format,
+                        AngleFormat::DegreesExpression,
+                        "Degrees (45*pi/180)",
+                    );
+                });
+                ui.checkbox(
+                    &mut export_format.omit_defaults,
+                    "Leave out fields that are the game's defaults",
+                );
+
+                ui.label("Field order:");
+                let mut moved_option = None;
+                let field_count = export_format.field_order.len();
+                export_format
+                    .field_order
+                    .iter()
+                    .enumerate()
+                    .for_each(|(idx, field)| {
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(idx > 0, Button::new("⏶")).clicked() {
+                                moved_option = Some((idx, idx - 1));
+                            }
+                            if ui
+                                .add_enabled(idx + 1 < field_count, Button::new("⏷"))
+                                .clicked()
+                            {
+                                moved_option = Some((idx, idx + 1));
+                            }
+                            ui.label(field.name());
+                        });
+                    });
+                if let Some((a, b)) = moved_option {
+                    export_format.field_order.swap(a, b);
+                }
+            });
+    }
+
+    fn export_preset_buttons(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let mut selected_option = None;
+            ComboBox::from_id_salt("export_preset")
+                .selected_text("Presets")
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(false, "Default").clicked() {
+                        selected_option = Some((String::new(), ExportFormat::default()));
+                    }
+                    self.export_presets
+                        .iter()
+                        .for_each(|(name, export_format)| {
+                            if ui
+                                .selectable_label(
+                                    *name == self.export_preset_name
+                                        && *export_format == self.export_format,
+                                    name,
+                                )
+                                .clicked()
+                            {
+                                selected_option = Some((name.clone(), export_format.clone()));
+                            }
+                        });
+                });
+            if let Some((name, export_format)) = selected_option {
+                self.export_preset_name = name;
+                self.export_format = export_format;
+            }
+        });
+        ui.horizontal(|ui| {
+            let text_edit = ui.add(
+                TextEdit::singleline(&mut self.export_preset_name)
+                    .hint_text("Preset name")
+                    .desired_width(120.0),
+            );
+            if text_edit.has_focus() {
+                self.visual_panel_key_bindings_enabled = false;
+            }
+            let name = self.export_preset_name.trim().to_string();
+            let existing_idx_option = self
+                .export_presets
+                .iter()
+                .position(|(preset_name, _)| *preset_name == name);
+            let save_button_text = if existing_idx_option.is_some() {
+                "Overwrite Preset"
+            } else {
+                "Save Preset"
+            };
+            if ui
+                .add_enabled(!name.is_empty(), Button::new(save_button_text))
+                .clicked()
+            {
+                match existing_idx_option {
+                    Some(existing_idx) => {
+                        self.export_presets[existing_idx].1 = self.export_format.clone()
+                    }
+                    None => self
+                        .export_presets
+                        .push((name.clone(), self.export_format.clone())),
+                }
+                save_export_presets(&self.export_presets);
+            }
+            if let Some(existing_idx) = existing_idx_option
+                && ui.button("Delete Preset").clicked()
+            {
+                self.export_presets.remove(existing_idx);
+                save_export_presets(&self.export_presets);
+            }
+        });
+    }
+}
@@ -0,0 +1,43 @@
+use std::{fs::File, io::Write, path::PathBuf};
+
+use egui::Ui;
+
+use crate::{
+    export::export_shapes, file_import_export::WhichFileDialog, shroud_editor::ShroudEditor,
+};
+
+impl ShroudEditor {
+    pub fn export_shapes(&self) -> String {
+        export_shapes(&self.loaded_shapes, &self.loaded_shapes_mirror_pairs)
+    }
+
+    pub fn export_shapes_to_file_button(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let response = ui.button("Export Shapes as File");
+            if response.clicked() {
+                self.save_file_dialog(WhichFileDialog::ShapeExport, "shapes.lua");
+            }
+            if let Some(just_exported_shapes_to_file_status) =
+                self.just_exported_shapes_to_file_status
+            {
+                if response.contains_pointer() {
+                    if just_exported_shapes_to_file_status {
+                        ui.label("Exported to file.");
+                    } else {
+                        ui.label("Failed :(");
+                    }
+                } else {
+                    self.just_exported_shapes_to_file_status = None
+                }
+            }
+        });
+    }
+
+    pub fn export_shapes_to_file(&mut self, path: PathBuf) {
+        let shapes_export = self.export_shapes();
+        let just_exported_shapes_to_file_status = File::create(path)
+            .and_then(|mut file| file.write_all(shapes_export.as_bytes()))
+            .is_ok();
+        self.just_exported_shapes_to_file_status = Some(just_exported_shapes_to_file_status)
+    }
+}
@@ -2,57 +2,19 @@ use std::{fs::File, io::Write, path::PathBuf};
 
 use arboard::Clipboard;
 use egui::Ui;
-use luexks_reassembly::{
-    blocks::{shroud::Shroud, shroud_layer::ShroudLayer},
-    utility::{component_formatting::format_component, display_oriented_math::do3d_float_from},
-};
 
-use crate::{file_import_export::WhichFileDialog, shroud_editor::ShroudEditor};
+use crate::{
+    export::export_shroud, file_import_export::WhichFileDialog, shroud_editor::ShroudEditor,
+};
 
 impl ShroudEditor {
-    fn export_shroud(&self) -> String {
-        let shroud = format_component(
-            Shroud(
-                self.shroud
-                    .iter()
-                    .map(|shroud_layer_container| {
-                        let shroud_layer = shroud_layer_container.shroud_layer.clone();
-                        let pre_block_offset_offset = shroud_layer.offset.as_ref().unwrap();
-                        let post_block_offset_offset = do3d_float_from(
-                            pre_block_offset_offset.x.to_f32() - self.block_container.offset.x,
-                            pre_block_offset_offset.y.to_f32() - self.block_container.offset.y,
-                            pre_block_offset_offset.z.to_f32(),
-                        );
-                        ShroudLayer {
-                            angle: if shroud_layer
-                                .angle
-                                .clone()
-                                .unwrap()
-                                .as_radians()
-                                .get_value()
-                                .abs()
-                                < f32::EPSILON
-                            {
-                                None
-                            } else {
-                                shroud_layer.angle.clone()
-                            },
-                            taper: if shroud_layer_container.shape_id != "SQUARE"
-                                || shroud_layer.taper.unwrap() == 1.0
-                            {
-                                None
-                            } else {
-                                shroud_layer.taper
-                            },
-                            offset: Some(post_block_offset_offset),
-                            ..shroud_layer
-                        }
-                    })
-                    .collect(),
-            ),
-            "shroud",
-        );
-        shroud.to_string()
+    pub fn export_shroud(&self) -> String {
+        export_shroud(
+            &self.shroud,
+            self.block_container.offset,
+            &self.shroud_trailing_comments,
+            &self.export_format,
+        )
     }
 
     pub fn export_shroud_to_clipboard_button(&mut self, ui: &mut Ui) {
@@ -110,8 +72,7 @@ impl ShroudEditor {
         ui.horizontal(|ui| {
             let response = ui.button("Export Shroud as File");
             if response.clicked() {
-                self.which_file_import = WhichFileDialog::ShroudExport;
-                self.file_dialog.save_file();
+                self.save_file_dialog(WhichFileDialog::ShroudExport, "shroud.lua");
             }
             if let Some(just_exported_to_file_status) = self.just_exported_to_file_status {
                 if response.contains_pointer() {
@@ -4,6 +4,10 @@ use itertools::Itertools;
 
 use crate::{
     keybinds::is_shortcut_pressed,
+    layer_links::{
+        cull_groups, groups_logic_for_deleted_layer_idx,
+        layer_group_idx_logic_for_deleted_groups_group_idx, mirror_idx_logic_for_deleted_layer_idx,
+    },
     position_conversion::world_pos_to_screen_pos,
     shroud_editor::{
         ShroudEditor,
@@ -58,25 +62,7 @@ impl ShroudEditor {
     }
 
     pub fn cull_groups(&mut self) {
-        self.groups
-            .iter()
-            .enumerate()
-            .rev()
-            .for_each(|(groups_group_idx, group)| {
-                if group.is_empty() {
-                    layer_group_idx_logic_for_deleted_groups_group_idx(
-                        &mut self.shroud,
-                        groups_group_idx,
-                    );
-                } else if group.len() == 1 {
-                    self.shroud[group[0]].group_idx_option = None;
-                    layer_group_idx_logic_for_deleted_groups_group_idx(
-                        &mut self.shroud,
-                        groups_group_idx,
-                    );
-                }
-            });
-        self.groups.retain(|group| group.len() >= 2);
+        cull_groups(&mut self.shroud, &mut self.groups);
     }
 
     pub fn get_one_entire_selected_group_idx_option(&self, selection: &[usize]) -> Option<usize> {
@@ -188,29 +174,11 @@ impl ShroudEditor {
     }
 
     pub fn groups_logic_for_deleted_layer_idx(&mut self, layer_idx: usize) {
-        self.groups.iter_mut().for_each(|group| {
-            if let Some(group_layer_idx_idx) = group
-                .iter()
-                .position(|group_layer_idx| *group_layer_idx == layer_idx)
-            {
-                group.remove(group_layer_idx_idx);
-            }
-            group.iter_mut().for_each(|group_layer_idx| {
-                if *group_layer_idx > layer_idx {
-                    *group_layer_idx -= 1;
-                }
-            });
-        });
+        groups_logic_for_deleted_layer_idx(&mut self.groups, layer_idx);
     }
 
     pub fn mirror_idx_logic_for_deleted_layer_idx(&mut self, layer_idx: usize) {
-        self.shroud.iter_mut().for_each(|shroud_layer_container| {
-            if let Some(mirror_index) = &mut shroud_layer_container.mirror_index_option
-                && *mirror_index > layer_idx
-            {
-                *mirror_index -= 1;
-            }
-        });
+        mirror_idx_logic_for_deleted_layer_idx(&mut self.shroud, layer_idx);
     }
 
     pub fn selection_logic_for_deleted_layer_idx(&mut self, layer_idx: usize) {
@@ -224,19 +192,6 @@ impl ShroudEditor {
     }
 }
 
-fn layer_group_idx_logic_for_deleted_groups_group_idx(
-    shroud: &mut [ShroudLayerContainer],
-    groups_group_idx: usize,
-) {
-    shroud.iter_mut().for_each(|layer| {
-        if let Some(layer_group_idx) = &mut layer.group_idx_option
-            && *layer_group_idx > groups_group_idx
-        {
-            *layer_group_idx -= 1;
-        }
-    });
-}
-
 const GROUP_OUTLINE_COLOUR: Rgba = Rgba::from_gray(200.0);
 
 pub fn render_group_outlines(
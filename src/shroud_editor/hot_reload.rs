@@ -0,0 +1,198 @@
+use std::{
+    fs::{metadata, read_to_string},
+    mem::replace,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use egui::{Context, Ui};
+use thiserror::Error;
+
+use crate::{
+    parse_shapes_text::ShapesMessage, parse_shroud_text::ShroudParseResult,
+    shroud_editor::ShroudEditor, shroud_interaction::ShroudInteraction,
+};
+
+const HOT_RELOAD_INTERVAL: f64 = 0.5;
+
+#[derive(Error, Debug)]
+pub enum HotReloadMessage {
+    #[error("Reloaded {0}.")]
+    Reloaded(String),
+
+    #[error("Failed to reload {0}, so nothing was changed: {1}")]
+    Failed(String, String),
+}
+
+#[derive(Default)]
+pub struct HotReload {
+    pub enabled: bool,
+    shapes_modified_option: Option<(PathBuf, SystemTime)>,
+    shroud_modified_option: Option<(PathBuf, SystemTime)>,
+    last_check_time: f64,
+    message_option: Option<HotReloadMessage>,
+}
+
+impl HotReload {
+    /// Notes the new modification time of a watched file the editor wrote itself, so it is not
+    /// reloaded.
+    pub fn note_file_written(&mut self, path: &Path) {
+        [
+            &mut self.shapes_modified_option,
+            &mut self.shroud_modified_option,
+        ]
+        .into_iter()
+        .for_each(|modified_option| {
+            if let Some((watched_path, modified)) = modified_option
+                && watched_path == path
+                && let Some(new_modified) = modified_time(path)
+            {
+                *modified = new_modified;
+            }
+        });
+    }
+}
+
+/// Whether the file at `path_option` was modified since the last call. A path that was not the
+/// one last checked only has its modification time noted.
+fn has_changed(
+    path_option: Option<&PathBuf>,
+    modified_option: &mut Option<(PathBuf, SystemTime)>,
+) -> bool {
+    let Some(path) = path_option else {
+        *modified_option = None;
+        return false;
+    };
+    let Some(modified) = modified_time(path) else {
+        return false;
+    };
+    let changed = matches!(
+        modified_option,
+        Some((last_path, last_modified)) if last_path == path && *last_modified != modified
+    );
+    *modified_option = Some((path.clone(), modified));
+    changed
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+impl ShroudEditor {
+    pub fn hot_reload_settings(&mut self, ui: &mut Ui) {
+        ui.checkbox(
+            &mut self.hot_reload.enabled,
+            "Reload the imported shapes and shroud files when they change",
+        )
+        .on_hover_text("Only the current tab's shroud file is watched.");
+        if self.hot_reload.enabled
+            && let Some(message) = &self.hot_reload.message_option
+        {
+            ui.label(message.to_string());
+        }
+    }
+
+    /// Checks the last imported shapes file and the current tab's shroud file every so often.
+    /// The shroud is not reloaded while layers are being dragged or placed.
+    pub fn hot_reload_logic(&mut self, ctx: &Context) {
+        if !self.hot_reload.enabled {
+            return;
+        }
+        let now = ctx.input(|i| i.time);
+        if now - self.hot_reload.last_check_time < HOT_RELOAD_INTERVAL {
+            return;
+        }
+        self.hot_reload.last_check_time = now;
+        if has_changed(
+            self.shapes_import_path_option.as_ref(),
+            &mut self.hot_reload.shapes_modified_option,
+        ) {
+            self.hot_reload_shapes();
+        }
+        if matches!(self.shroud_interaction, ShroudInteraction::Inaction { .. })
+            && has_changed(
+                self.shroud_import_path_option.as_ref(),
+                &mut self.hot_reload.shroud_modified_option,
+            )
+        {
+            self.hot_reload_shroud();
+        }
+    }
+
+    fn hot_reload_shapes(&mut self) {
+        let Some(path) = self.shapes_import_path_option.clone() else {
+            return;
+        };
+        let result = read_to_string(&path)
+            .map_err(|_| ShapesMessage::CouldNotOpenFile)
+            .and_then(|shapes_text| self.import_shapes_text(&shapes_text));
+        self.hot_reload.message_option = Some(match result {
+            Ok(()) => HotReloadMessage::Reloaded(file_name(&path)),
+            Err(err) => HotReloadMessage::Failed(file_name(&path), err.to_string()),
+        });
+    }
+
+    /// Keeps the selection and the groups if the number of layers has not changed, as they refer to
+    /// layers by index. Even in lenient mode, a file with any problem in it changes nothing, as
+    /// applying what could be read would drop the rest of the layers.
+    fn hot_reload_shroud(&mut self) {
+        let Some(path) = self.shroud_import_path_option.clone() else {
+            return;
+        };
+        let Ok(shroud_text) = read_to_string(&path) else {
+            self.hot_reload.message_option = Some(HotReloadMessage::Failed(
+                file_name(&path),
+                ShroudParseResult::CouldNotOpenFile.to_string(),
+            ));
+            return;
+        };
+        let shroud_import =
+            match self.parse_shroud_import(&shroud_text, self.block_container.offset) {
+                Ok(shroud_import) if shroud_import.problems().is_empty() => shroud_import,
+                Ok(shroud_import) => {
+                    let problems = shroud_import
+                        .problems()
+                        .iter()
+                        .map(|problem| problem.to_string())
+                        .collect::<Vec<_>>()
+                        .join("; ");
+                    self.hot_reload.message_option =
+                        Some(HotReloadMessage::Failed(file_name(&path), problems));
+                    return;
+                }
+                Err(err) => {
+                    self.hot_reload.message_option =
+                        Some(HotReloadMessage::Failed(file_name(&path), err.to_string()));
+                    return;
+                }
+            };
+        let selection = self.shroud_interaction.selection();
+        let groups = self.groups.clone();
+        let group_idx_options = self
+            .shroud
+            .iter()
+            .map(|shroud_layer| shroud_layer.group_idx_option)
+            .collect::<Vec<_>>();
+
+        let append_shroud_import = replace(&mut self.append_shroud_import, false);
+        self.apply_shroud_import(shroud_import);
+        self.append_shroud_import = append_shroud_import;
+
+        if self.shroud.len() == group_idx_options.len() {
+            self.shroud_interaction = ShroudInteraction::Inaction { selection };
+            self.groups = groups;
+            self.shroud.iter_mut().zip(group_idx_options).for_each(
+                |(shroud_layer, group_idx_option)| {
+                    shroud_layer.group_idx_option = group_idx_option;
+                },
+            );
+        }
+        self.hot_reload.message_option = Some(HotReloadMessage::Reloaded(file_name(&path)));
+    }
+}
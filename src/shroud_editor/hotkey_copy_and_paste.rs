@@ -1,55 +1,76 @@
-use egui::Context;
+use arboard::Clipboard;
+use egui::{Context, Pos2};
+use luexks_reassembly::utility::display_oriented_math::do3d_float_from;
 
 use crate::{
+    add_mirror::link_shroud_layer_mirrors,
     invert_y::invert_y_of_pos2,
     keybinds::is_shortcut_pressed,
+    parse_shroud_text::parse_shroud_text,
     pos_and_display_oriented_number_conversion::do3d_to_pos2,
-    shroud_editor::{ShroudEditor, add_mirror::add_mirror},
+    project_format::format_shroud_layer_exact,
+    shroud_editor::ShroudEditor,
     shroud_interaction::{MovingShroudLayerInteraction, MovingShroudSelection, ShroudInteraction},
     shroud_layer_container::ShroudLayerContainer,
 };
 
 impl ShroudEditor {
+    /// Mirrors are copied along with the selection, with `mirror_index_option` pointing into the
+    /// clipboard. The layers also go on the system clipboard as shroud text.
     pub fn hotkey_copy(&mut self, ctx: &Context) {
         if is_shortcut_pressed(ctx, &self.keybinds.copy) {
-            self.shroud_clipboard = self
-                .shroud_interaction
-                .selection()
+            let (whole_selection, _, _) = self.get_selection_mirror_split();
+            self.shroud_clipboard = whole_selection
                 .iter()
-                .map(|index| self.shroud[*index].clone())
+                .map(|index| ShroudLayerContainer {
+                    mirror_index_option: self.shroud[*index].mirror_index_option.and_then(
+                        |mirror_index| {
+                            whole_selection
+                                .iter()
+                                .position(|index| *index == mirror_index)
+                        },
+                    ),
+                    group_idx_option: None,
+                    comments: Vec::new(),
+                    ..self.shroud[*index].clone()
+                })
                 .collect();
+            self.shroud_clipboard_text =
+                clipboard_shroud_text(&self.shroud_clipboard, self.block_container.offset);
+            if let Ok(mut clipboard) = Clipboard::new() {
+                let _ = clipboard.set_text(self.shroud_clipboard_text.clone());
+            }
         }
     }
+
     pub fn hotkey_paste(&mut self, ctx: &Context) {
         if let ShroudInteraction::Placing { .. } = self.shroud_interaction {
-        } else if is_shortcut_pressed(ctx, &self.keybinds.paste)
-            && !self.shroud_clipboard.is_empty()
-        {
-            let mut to_be_selected_indexes = Vec::new();
-            // let entire_one_group_idx_option = self.get_one_entire_group_idx_option_of_layers(&self.shroud_clipboard);
-            self.shroud_clipboard
-                .iter()
-                .for_each(|shroud_layer_container| {
-                    let new_shroud_layer_container = ShroudLayerContainer {
-                        group_idx_option: None,
-                        ..shroud_layer_container.clone()
-                    };
-                    let last = self.shroud.len();
-                    self.shroud.push(new_shroud_layer_container);
-                    to_be_selected_indexes.push(last);
-                    if let Some(_mirror_index) = self.shroud[last].mirror_index_option {
-                        add_mirror(
-                            &mut self.shroud,
-                            last,
-                            true,
-                            &self.loaded_shapes,
-                            &self.loaded_shapes_mirror_pairs,
-                        );
+        } else if is_shortcut_pressed(ctx, &self.keybinds.paste) {
+            let pasted_shroud = self
+                .system_clipboard_shroud_option()
+                .unwrap_or_else(|| self.shroud_clipboard.clone());
+            if pasted_shroud.is_empty() {
+                return;
+            }
+            let first_pasted_idx = self.shroud.len();
+            self.shroud
+                .extend(pasted_shroud.into_iter().map(|mut shroud_layer_container| {
+                    if let Some(mirror_index) = &mut shroud_layer_container.mirror_index_option {
+                        *mirror_index += first_pasted_idx;
                     }
-                });
+                    shroud_layer_container
+                }));
+            // Mirrors follow the layer they mirror, so only one of each pair is placed.
+            let to_be_selected_indexes = (first_pasted_idx..self.shroud.len())
+                .filter(|idx| {
+                    self.shroud[*idx]
+                        .mirror_index_option
+                        .is_none_or(|mirror_index| mirror_index > *idx)
+                })
+                .collect::<Vec<_>>();
             let world_mouse_pos_inverted_y = invert_y_of_pos2(self.world_mouse_pos);
             let drag_pos = do3d_to_pos2(
-                self.shroud_clipboard[0]
+                self.shroud[first_pasted_idx]
                     .shroud_layer
                     .offset
                     .as_ref()
@@ -75,4 +96,50 @@ impl ShroudEditor {
             };
         }
     }
+
+    /// Shroud text copied from somewhere else, like another editor window or a text editor.
+    /// Mirror pairs are linked again, since shroud text does not record them.
+    fn system_clipboard_shroud_option(&self) -> Option<Vec<ShroudLayerContainer>> {
+        let text = Clipboard::new().ok()?.get_text().ok()?;
+        if text == self.shroud_clipboard_text {
+            return None;
+        }
+        let (mut shroud, _) = parse_shroud_text(&text, &self.loaded_shapes).ok()?;
+        shroud.iter_mut().for_each(|shroud_layer| {
+            let offset = shroud_layer.shroud_layer.offset.as_ref().unwrap();
+            shroud_layer.shroud_layer.offset = Some(do3d_float_from(
+                offset.x.to_f32() + self.block_container.offset.x,
+                offset.y.to_f32() + self.block_container.offset.y,
+                offset.z.to_f32(),
+            ));
+            shroud_layer.comments.clear();
+        });
+        link_shroud_layer_mirrors(
+            &mut shroud,
+            &self.loaded_shapes,
+            &self.loaded_shapes_mirror_pairs,
+        );
+        Some(shroud)
+    }
+}
+
+/// Shroud text at full precision, whatever the export settings are, so pasting it back gives the
+/// same layers. Offsets are relative to the block, as in an export.
+fn clipboard_shroud_text(shroud: &[ShroudLayerContainer], block_offset: Pos2) -> String {
+    let mut s = String::from("shroud={\n");
+    shroud.iter().for_each(|shroud_layer_container| {
+        let mut shroud_layer_container = shroud_layer_container.clone();
+        let shroud_layer = &mut shroud_layer_container.shroud_layer;
+        let offset = shroud_layer.offset.as_ref().unwrap();
+        shroud_layer.offset = Some(do3d_float_from(
+            offset.x.to_f32() - block_offset.x,
+            offset.y.to_f32() - block_offset.y,
+            offset.z.to_f32(),
+        ));
+        s.push('\t');
+        s.push_str(&format_shroud_layer_exact(&shroud_layer_container));
+        s.push('\n');
+    });
+    s.push('}');
+    s
 }
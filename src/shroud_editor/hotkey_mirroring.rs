@@ -1,9 +1,6 @@
 use egui::Context;
 
-use crate::{
-    keybinds::is_shortcut_pressed,
-    shroud_editor::{ShroudEditor, add_mirror::add_mirror},
-};
+use crate::{add_mirror::add_mirror, keybinds::is_shortcut_pressed, shroud_editor::ShroudEditor};
 
 impl ShroudEditor {
     pub fn hotkey_mirroring(&mut self, ctx: &Context) {
@@ -19,7 +16,6 @@ impl ShroudEditor {
                         add_mirror(
                             &mut self.shroud,
                             *shroud_layer_index,
-                            false,
                             &self.loaded_shapes,
                             &self.loaded_shapes_mirror_pairs,
                         );
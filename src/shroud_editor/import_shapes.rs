@@ -1,35 +1,35 @@
 use std::{fs::read_to_string, path::PathBuf};
 
-use egui::{ScrollArea, TextBuffer, TextEdit, Ui, collapsing_header::CollapsingState};
-use egui_extras::syntax_highlighting::{CodeTheme, highlight};
-use parse_vanilla_shapes::VANILLA_SHAPE_COUNT;
+use egui::{Color32, Ui, collapsing_header::CollapsingState};
+use itertools::Itertools;
 
 use crate::{
     file_import_export::WhichFileDialog,
     mirror_pairs::get_loaded_shapes_mirror_pairs,
+    parse_shapes_text::{ShapesMessage, parse_shapes_text},
     restructure_vertices::restructure_vertices,
-    shape_container::ShapeContainer,
+    shape_container::{ShapeContainer, with_imported_shapes},
     shroud_editor::{
         ShroudEditor,
-        parse_shapes_text::{ShapesMessage, parse_shapes_text},
+        paste_box::{PasteBoxError, paste_box_text_edit},
     },
 };
 
 impl ShroudEditor {
-    fn load_shapes(
+    pub fn import_shapes_text(&mut self, shapes_text: &str) -> Result<(), ShapesMessage> {
+        let (imported_shapes, mirror_pairs, non_mirrors) = parse_shapes_text(shapes_text)?;
+        self.load_shapes(imported_shapes, mirror_pairs, non_mirrors);
+        self.loaded_shapes_text = shapes_text.to_string();
+        Ok(())
+    }
+
+    pub fn load_shapes(
         &mut self,
         imported_shapes: Vec<ShapeContainer>,
         mirror_pairs: Vec<(usize, usize)>,
         non_mirrors: Vec<usize>,
     ) {
-        self.loaded_shapes = self.loaded_shapes[0..VANILLA_SHAPE_COUNT]
-            .iter()
-            .cloned()
-            .chain(imported_shapes)
-            .collect();
-        non_mirrors.into_iter().for_each(|non_mirror| {
-            self.loaded_shapes[non_mirror].set_invert_height_of_mirror();
-        });
+        self.loaded_shapes = self.shapes_with_vanilla(imported_shapes, &non_mirrors);
         self.loaded_shapes_mirror_pairs = get_loaded_shapes_mirror_pairs(&self.loaded_shapes);
         self.loaded_shapes_mirror_pairs.extend(mirror_pairs);
         (0..self.shroud.len()).for_each(|shroud_layer_index| {
@@ -41,7 +41,8 @@ impl ShroudEditor {
                     self.loaded_shapes[shape_idx].s.get_first_scale_vertices(),
                 );
                 shroud_layer.invert_height_of_mirror =
-                    self.loaded_shapes[shape_idx].invert_height_of_mirror
+                    self.loaded_shapes[shape_idx].invert_height_of_mirror;
+                shroud_layer.shape_is_missing = false;
             }
         });
         if let Some(shape_idx) = self.loaded_shapes.iter().position(|shape| {
@@ -50,6 +51,39 @@ impl ShroudEditor {
             self.block_container.vertices =
                 restructure_vertices(self.loaded_shapes[shape_idx].s.get_first_scale_vertices());
         }
+        self.invalidate_mod_folder_thumbnails();
+    }
+
+    /// The shapes that `load_shapes` would load, without loading them.
+    pub fn shapes_with_vanilla(
+        &self,
+        imported_shapes: Vec<ShapeContainer>,
+        non_mirrors: &[usize],
+    ) -> Vec<ShapeContainer> {
+        with_imported_shapes(&self.loaded_shapes, imported_shapes, non_mirrors)
+    }
+
+    /// Each shape ID used by a shroud layer that is not loaded, once.
+    pub fn missing_shape_ids(&self) -> Vec<String> {
+        self.shroud
+            .iter()
+            .filter(|shroud_layer| shroud_layer.shape_is_missing)
+            .map(|shroud_layer| shroud_layer.shape_id.clone())
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    pub fn missing_shapes_list(&self, ui: &mut Ui) {
+        let missing_shape_ids = self.missing_shape_ids();
+        if missing_shape_ids.is_empty() {
+            return;
+        }
+        ui.colored_label(
+            Color32::RED,
+            format!("Shapes not loaded: {}", missing_shape_ids.join(", ")),
+        )
+        .on_hover_text("These layers are drawn as crossed out placeholders until a shapes file with these IDs is imported.");
     }
 
     pub fn import_shapes_from_file_button(&mut self, ui: &mut Ui) {
@@ -69,17 +103,13 @@ impl ShroudEditor {
     }
 
     pub fn import_shapes_from_file(&mut self, path: PathBuf) {
-        if let Ok(s) = read_to_string(path) {
-            match parse_shapes_text(&s) {
-                Ok((imported_shapes, mirror_pairs, non_mirrors)) => {
-                    self.load_shapes(imported_shapes, mirror_pairs, non_mirrors);
-                    self.just_imported_shapes_from_file_message_option =
-                        Some(ShapesMessage::Success);
-                }
-                Err(err) => {
-                    self.just_imported_shapes_from_file_message_option = Some(err);
-                }
-            }
+        if let Ok(s) = read_to_string(&path) {
+            self.shapes_import_path_option = Some(path);
+            self.just_imported_shapes_from_file_message_option = Some(
+                self.import_shapes_text(&s)
+                    .err()
+                    .unwrap_or(ShapesMessage::Success),
+            );
         } else {
             self.just_imported_shapes_from_file_message_option =
                 Some(ShapesMessage::CouldNotOpenFile);
@@ -93,20 +123,19 @@ impl ShroudEditor {
             })
             .body(|ui| {
                 ui.label("READ: Keep all custom shapes in paste box.");
-                ui.label("READ: If a custom shape used by a shroud layer is not reimported, nothing significant will happen, but it will just be weird.");
                 ui.horizontal(|ui| {
                     let response = ui.button("Import");
                     if response.clicked() {
-                        match parse_shapes_text(&self.shapes_import_text) {
-                            Ok((imported_shapes, mirror_pairs, non_mirrors)) => {
-                                self.load_shapes(imported_shapes, mirror_pairs, non_mirrors);
-                                self.just_imported_shapes_from_paste_box_message_option =
-                                    Some(ShapesMessage::Success);
-                            }
-                            Err(err) => {
-                                self.just_imported_shapes_from_paste_box_message_option = Some(err);
-                            }
-                        }
+                        let shapes_import_text = self.shapes_import_text.clone();
+                        let result = self.import_shapes_text(&shapes_import_text);
+                        self.shapes_paste_box_error_option = result
+                            .as_ref()
+                            .err()
+                            .and_then(ShapesMessage::location)
+                            .cloned()
+                            .map(PasteBoxError::new);
+                        self.just_imported_shapes_from_paste_box_message_option =
+                            Some(result.err().unwrap_or(ShapesMessage::Success));
                     }
                     if let Some(message) = &self.just_imported_shapes_from_paste_box_message_option
                     {
@@ -116,24 +145,14 @@ impl ShroudEditor {
                         self.just_imported_shapes_from_paste_box_message_option = None;
                     }
                 });
-                ScrollArea::horizontal().show(ui, |ui| {
-                    let theme = CodeTheme::light(12.0);
-                    let mut layouter = |ui: &Ui, buf: &dyn TextBuffer, wrap_width: f32| {
-                        let mut layout_job =
-                            highlight(ui.ctx(), ui.style(), &theme, buf.as_str(), "toml");
-                        layout_job.wrap.max_width = wrap_width;
-                        ui.fonts_mut(|f| f.layout_job(layout_job))
-                    };
-                    let text_edit = ui.add(
-                        TextEdit::multiline(&mut self.shapes_import_text)
-                            .code_editor()
-                            .desired_width(f32::INFINITY)
-                            .layouter(&mut layouter),
-                    );
-                    if text_edit.has_focus() {
-                        self.visual_panel_key_bindings_enabled = false;
-                    }
-                });
+                let text_edit = paste_box_text_edit(
+                    ui,
+                    &mut self.shapes_import_text,
+                    &mut self.shapes_paste_box_error_option,
+                );
+                if text_edit.has_focus() {
+                    self.visual_panel_key_bindings_enabled = false;
+                }
             });
     }
 }
@@ -0,0 +1,197 @@
+use std::{fs::read_to_string, path::PathBuf};
+
+use egui::{Pos2, ScrollArea, Ui, collapsing_header::CollapsingState};
+use luexks_reassembly::utility::display_oriented_math::do3d_float_from;
+
+use crate::{
+    add_mirror::link_shroud_layer_mirrors,
+    file_import_export::WhichFileDialog,
+    parse_shroud_text::{ShroudParseResult, parse_shroud_text, parse_shroud_text_lenient},
+    shroud_editor::{ShroudEditor, live_link::LiveLinkTarget, preferences::RecentFileKind},
+    shroud_interaction::ShroudInteraction,
+    shroud_layer_container::ShroudLayerContainer,
+};
+
+/// A shroud that was parsed for import, with the block offset added, but not applied yet.
+pub struct ShroudImport {
+    shroud: Vec<ShroudLayerContainer>,
+    trailing_comments: Vec<String>,
+    problems: Vec<ShroudParseResult>,
+}
+
+impl ShroudImport {
+    /// What lenient parsing could not read. Always empty when not in lenient mode.
+    pub fn problems(&self) -> &[ShroudParseResult] {
+        &self.problems
+    }
+}
+
+impl ShroudEditor {
+    /// In lenient mode, this only fails if no layer at all could be read, and the problems are kept
+    /// in `shroud_import_problems`. In append mode the imported layers are added after the current
+    /// ones and selected, and groups are kept.
+    pub fn import_shroud_text(&mut self, shroud_text: &str) -> Result<(), ShroudParseResult> {
+        self.shroud_import_problems.clear();
+        let shroud_import = self.parse_shroud_import(shroud_text, self.block_container.offset)?;
+        self.apply_shroud_import(shroud_import);
+        Ok(())
+    }
+
+    /// Parses `shroud_text` as `import_shroud_text` would, for a block with `block_offset`, without
+    /// changing anything.
+    pub fn parse_shroud_import(
+        &self,
+        shroud_text: &str,
+        block_offset: Pos2,
+    ) -> Result<ShroudImport, ShroudParseResult> {
+        let (mut imported_shroud, trailing_comments, problems) = if self.lenient_shroud_import {
+            let (imported_shroud, trailing_comments, mut problems) =
+                parse_shroud_text_lenient(shroud_text, &self.loaded_shapes);
+            if imported_shroud.is_empty() && !problems.is_empty() {
+                return Err(problems.remove(0));
+            }
+            (imported_shroud, trailing_comments, problems)
+        } else {
+            let (imported_shroud, trailing_comments) =
+                parse_shroud_text(shroud_text, &self.loaded_shapes)?;
+            (imported_shroud, trailing_comments, Vec::new())
+        };
+        imported_shroud.iter_mut().for_each(|shroud_layer| {
+            let pre_block_offset_offset = shroud_layer.shroud_layer.offset.as_ref().unwrap();
+            let post_block_offset_offset = do3d_float_from(
+                pre_block_offset_offset.x.to_f32() + block_offset.x,
+                pre_block_offset_offset.y.to_f32() + block_offset.y,
+                pre_block_offset_offset.z.to_f32(),
+            );
+            shroud_layer.shroud_layer.offset = Some(post_block_offset_offset);
+        });
+        if self.link_mirrors_on_import {
+            link_shroud_layer_mirrors(
+                &mut imported_shroud,
+                &self.loaded_shapes,
+                &self.loaded_shapes_mirror_pairs,
+            );
+        }
+        Ok(ShroudImport {
+            shroud: imported_shroud,
+            trailing_comments,
+            problems,
+        })
+    }
+
+    pub fn apply_shroud_import(&mut self, shroud_import: ShroudImport) {
+        let ShroudImport {
+            shroud: mut imported_shroud,
+            trailing_comments,
+            problems,
+        } = shroud_import;
+        self.shroud_import_problems = problems;
+        if self.append_shroud_import {
+            let first_imported_idx = self.shroud.len();
+            imported_shroud.iter_mut().for_each(|shroud_layer| {
+                if let Some(mirror_index) = &mut shroud_layer.mirror_index_option {
+                    *mirror_index += first_imported_idx;
+                }
+            });
+            self.shroud.extend(imported_shroud);
+            self.shroud_trailing_comments.extend(trailing_comments);
+            self.shroud_interaction = ShroudInteraction::Inaction {
+                selection: (first_imported_idx..self.shroud.len()).collect(),
+            };
+        } else {
+            self.shroud = imported_shroud;
+            self.shroud_trailing_comments = trailing_comments;
+            self.shroud_interaction = ShroudInteraction::Inaction {
+                selection: Vec::new(),
+            };
+            self.groups.clear();
+        }
+        self.add_undo_history = true;
+    }
+
+    pub fn import_shroud_from_file_button(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let response = ui.button("Import Shroud from File");
+            if response.clicked() {
+                self.which_file_import = WhichFileDialog::ShroudImport;
+                self.file_dialog.pick_file();
+            }
+            let reimport_response_option = self
+                .shroud_import_path_option
+                .clone()
+                .map(|path| (ui.button("Reimport"), path));
+            if let Some((reimport_response, path)) = &reimport_response_option
+                && reimport_response.clicked()
+            {
+                self.import_shroud_from_file(path.clone());
+            }
+            if let Some(message) = &self.just_imported_shroud_from_file_message_option {
+                ui.label(message.to_string());
+            }
+            if !response.contains_pointer()
+                && !reimport_response_option
+                    .is_some_and(|(reimport_response, _)| reimport_response.contains_pointer())
+            {
+                self.just_imported_shroud_from_file_message_option = None;
+            }
+        });
+        if let Some(path) = &self.shroud_import_path_option {
+            ui.small(path.display().to_string());
+        }
+    }
+
+    pub fn import_shroud_from_file(&mut self, path: PathBuf) {
+        if let Ok(s) = read_to_string(&path) {
+            self.add_recent_file(RecentFileKind::Shroud, path.clone());
+            self.shroud_import_path_option = Some(path.clone());
+            let result = self.import_shroud_text(&s);
+            if result.is_ok() && !self.append_shroud_import {
+                self.unlink_live_link_on_load(|live_link_target| {
+                    *live_link_target == LiveLinkTarget::ShroudFile(path.clone())
+                });
+            }
+            self.just_imported_shroud_from_file_message_option =
+                Some(self.shroud_import_message(result));
+        } else {
+            self.just_imported_shroud_from_file_message_option =
+                Some(ShroudParseResult::CouldNotOpenFile);
+        }
+    }
+
+    pub fn shroud_import_message(
+        &self,
+        result: Result<(), ShroudParseResult>,
+    ) -> ShroudParseResult {
+        match result {
+            Ok(()) if self.shroud_import_problems.is_empty() => ShroudParseResult::Success,
+            Ok(()) => ShroudParseResult::SuccessWithProblems(self.shroud_import_problems.len()),
+            Err(err) => err,
+        }
+    }
+
+    pub fn shroud_import_problems_list(&mut self, ui: &mut Ui) {
+        if self.shroud_import_problems.is_empty() {
+            return;
+        }
+        CollapsingState::load_with_default_open(ui.ctx(), "shroud_import_problems".into(), true)
+            .show_header(ui, |ui| {
+                ui.strong(format!(
+                    "Shroud Import Problems ({})",
+                    self.shroud_import_problems.len()
+                ));
+            })
+            .body(|ui| {
+                if ui.button("Clear").clicked() {
+                    self.shroud_import_problems.clear();
+                }
+                ScrollArea::vertical()
+                    .id_salt("shroud_import_problems_scroll")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        self.shroud_import_problems.iter().for_each(|problem| {
+                            ui.small(problem.to_string());
+                        });
+                    });
+            });
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    fs::{read_to_string, write},
+    path::PathBuf,
+};
+
+use egui::Ui;
+
+use crate::{
+    file_import_export::WhichFileDialog,
+    json_interchange::{JsonMessage, export_json, import_json},
+    shroud_editor::ShroudEditor,
+    shroud_interaction::ShroudInteraction,
+};
+
+impl ShroudEditor {
+    pub fn export_json(&self) -> String {
+        export_json(
+            &self.shroud,
+            &self.groups,
+            &self.shroud_trailing_comments,
+            self.block_container.offset,
+        )
+    }
+
+    /// Replaces the shroud and its groups.
+    pub fn import_json_text(&mut self, json_text: &str) -> Result<(), JsonMessage> {
+        let (shroud, groups, trailing_comments) =
+            import_json(json_text, &self.loaded_shapes, self.block_container.offset)?;
+        self.shroud = shroud;
+        self.groups = groups;
+        self.shroud_trailing_comments = trailing_comments;
+        self.shroud_interaction = ShroudInteraction::none();
+        self.add_undo_history = true;
+        self.unlink_live_link_on_load(|_| false);
+        Ok(())
+    }
+
+    pub fn json_file_buttons(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let import_response = ui.button("Import JSON");
+            if import_response.clicked() {
+                self.which_file_import = WhichFileDialog::JsonImport;
+                self.file_dialog.pick_file();
+            }
+            let export_response = ui.button("Export JSON");
+            if export_response.clicked() {
+                self.save_file_dialog(WhichFileDialog::JsonExport, "shroud.json");
+            }
+            if let Some(message) = &self.json_file_message_option {
+                ui.label(message.to_string());
+            }
+            if !import_response.contains_pointer() && !export_response.contains_pointer() {
+                self.json_file_message_option = None;
+            }
+        });
+    }
+
+    pub fn import_json_from_file(&mut self, path: PathBuf) {
+        self.json_file_message_option = Some(match read_to_string(path) {
+            Ok(s) => self
+                .import_json_text(&s)
+                .err()
+                .unwrap_or(JsonMessage::Opened),
+            Err(_) => JsonMessage::CouldNotOpenFile,
+        });
+    }
+
+    pub fn export_json_to_file(&mut self, path: PathBuf) {
+        self.json_file_message_option = Some(match write(path, self.export_json()) {
+            Ok(()) => JsonMessage::Saved,
+            Err(_) => JsonMessage::CouldNotWriteFile,
+        });
+    }
+}
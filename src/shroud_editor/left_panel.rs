@@ -1,13 +1,12 @@
 use egui::{
     Area, Checkbox, Color32, Context, DragValue, Frame, Grid, Id, Popup, PopupCloseBehavior, Pos2,
-    Rgba, ScrollArea, Slider, TextBuffer, TextEdit, Ui, Vec2,
+    Rgba, ScrollArea, Slider, TextEdit, Ui, Vec2,
     collapsing_header::CollapsingState,
     color_picker::{Alpha, color_edit_button_rgba},
     pos2,
     scroll_area::ScrollBarVisibility,
     vec2,
 };
-use egui_extras::syntax_highlighting::{CodeTheme, highlight};
 use luexks_reassembly::{
     blocks::shroud_layer::ShroudLayer,
     shapes::shape_id::ShapeId,
@@ -21,11 +20,12 @@ use parse_vanilla_shapes::VANILLA_SHAPE_COUNT;
 use crate::{
     color_type_conversion::{rgba_to_color, rgba_to_color_string, str_to_rgba_option},
     invert_y::invert_y_of_pos2,
+    parse_shroud_text::ShroudParseResult,
     restructure_vertices::restructure_vertices,
     shape_container::ShapeContainer,
     shroud_editor::{
         DRAG_VALUE_MAX, DRAG_VALUE_MIN, FILL_COLOR_GRADIENT_TIME, ShroudEditor,
-        parse_shroud_text::{ShroudParseResult, parse_shroud_text},
+        paste_box::{PasteBoxError, paste_box_text_edit},
     },
     shroud_interaction::{MovingShroudLayerInteraction, MovingShroudSelection, ShroudInteraction},
     shroud_layer_container::ShroudLayerContainer,
@@ -62,13 +62,39 @@ impl ShroudEditor {
         CollapsingState::load_with_default_open(ctx, "file".into(), false)
             .show_header(ui, |ui| ui.heading("File"))
             .body_unindented(|ui| {
+                self.project_file_buttons(ui);
+                self.recent_files_menu(ctx, ui);
+                self.mod_folder_settings(ui);
+                ui.separator();
                 self.export_shroud_to_clipboard_button(ui);
                 self.export_shroud_to_file_button(ui);
                 self.export_shroud_as_file_next_to_exe_button(ui);
+                self.export_format_settings(ctx, ui);
+                self.import_shroud_from_file_button(ui);
                 self.import_shroud_from_paste_box(ui);
-                ui.label("READ: If a custom shape used by a shroud layer is not reimported, nothing significant will happen, but it will just be weird.");
+                ui.checkbox(
+                    &mut self.link_mirrors_on_import,
+                    "Link mirrored layers when importing a shroud",
+                );
+                ui.checkbox(
+                    &mut self.lenient_shroud_import,
+                    "Import what can be read from a broken shroud",
+                );
+                ui.checkbox(
+                    &mut self.append_shroud_import,
+                    "Add imported layers to the current shroud instead of replacing it",
+                );
+                self.shroud_import_problems_list(ui);
+                self.missing_shapes_list(ui);
                 self.import_shapes_from_file_button(ui);
                 self.import_shapes_from_paste_box(ui);
+                self.export_shapes_to_file_button(ui);
+                self.hot_reload_settings(ui);
+                ui.separator();
+                self.json_file_buttons(ui);
+                ui.separator();
+                self.blocks_file_settings(ui);
+                self.live_link_settings(ui);
             });
     }
 
@@ -78,6 +104,7 @@ impl ShroudEditor {
             .body_unindented(|ui| {
                 self.background_grid_settings(ui);
                 self.angle_snap_settings(ui);
+                self.object_snap_settings(ui);
                 self.fill_color_gradient_setting(ui);
                 self.editor_shroud_layer_group_settings(ui);
                 self.binding_config(ctx, ui);
@@ -372,34 +399,20 @@ impl ShroudEditor {
                 ui.horizontal(|ui| {
                     let response = ui.button("Import (Double Click)");
                     if response.double_clicked() {
-                        match parse_shroud_text(&self.shroud_import_text, &self.loaded_shapes) {
-                            Ok(imported_shroud) => {
-                                self.shroud = imported_shroud;
-                                self.shroud.iter_mut().for_each(|shroud_layer| {
-                                    let pre_block_offset_offset =
-                                        shroud_layer.shroud_layer.offset.as_ref().unwrap();
-                                    let post_block_offset_offset = do3d_float_from(
-                                        pre_block_offset_offset.x.to_f32()
-                                            + self.block_container.offset.x,
-                                        pre_block_offset_offset.y.to_f32()
-                                            + self.block_container.offset.y,
-                                        pre_block_offset_offset.z.to_f32(),
-                                    );
-                                    shroud_layer.shroud_layer.offset =
-                                        Some(post_block_offset_offset);
-                                });
-                                self.just_imported_shroud_from_paste_box_message_option =
-                                    Some(ShroudParseResult::Success);
-                                self.shroud_interaction = ShroudInteraction::Inaction {
-                                    selection: Vec::new(),
-                                };
-                                self.groups.clear();
-                                self.add_undo_history = true;
-                            }
-                            Err(err) => {
-                                self.just_imported_shroud_from_paste_box_message_option = Some(err);
-                            }
+                        let shroud_import_text = self.shroud_import_text.clone();
+                        let result = self.import_shroud_text(&shroud_import_text);
+                        if result.is_ok() && !self.append_shroud_import {
+                            self.unlink_live_link_on_load(|_| false);
                         }
+                        self.shroud_paste_box_error_option = result
+                            .as_ref()
+                            .err()
+                            .or(self.shroud_import_problems.first())
+                            .and_then(ShroudParseResult::location)
+                            .cloned()
+                            .map(PasteBoxError::new);
+                        self.just_imported_shroud_from_paste_box_message_option =
+                            Some(self.shroud_import_message(result));
                     }
                     if let Some(message) = &self.just_imported_shroud_from_paste_box_message_option
                     {
@@ -409,24 +422,14 @@ impl ShroudEditor {
                         self.just_imported_shroud_from_paste_box_message_option = None;
                     }
                 });
-                ScrollArea::horizontal().show(ui, |ui| {
-                    let theme = CodeTheme::light(12.0);
-                    let mut layouter = |ui: &Ui, buf: &dyn TextBuffer, wrap_width: f32| {
-                        let mut layout_job =
-                            highlight(ui.ctx(), ui.style(), &theme, buf.as_str(), "toml");
-                        layout_job.wrap.max_width = wrap_width;
-                        ui.fonts_mut(|f| f.layout_job(layout_job))
-                    };
-                    let text_edit = ui.add(
-                        TextEdit::multiline(&mut self.shroud_import_text)
-                            .code_editor()
-                            .desired_width(f32::INFINITY)
-                            .layouter(&mut layouter),
-                    );
-                    if text_edit.has_focus() {
-                        self.visual_panel_key_bindings_enabled = false;
-                    }
-                });
+                let text_edit = paste_box_text_edit(
+                    ui,
+                    &mut self.shroud_import_text,
+                    &mut self.shroud_paste_box_error_option,
+                );
+                if text_edit.has_focus() {
+                    self.visual_panel_key_bindings_enabled = false;
+                }
             });
     }
 
@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+
+use egui::{Button, Ui, collapsing_header::CollapsingState};
+use thiserror::Error;
+
+use crate::{
+    file_import_export::{WhichFileDialog, write_file_atomically},
+    shroud_editor::{ShroudEditor, blocks_file::write_block_shroud_to_file},
+};
+
+/// Where the shroud is written to after every edit. Each tab has its own.
+#[derive(Clone, PartialEq)]
+pub enum LiveLinkTarget {
+    ShroudFile(PathBuf),
+    Block {
+        path: PathBuf,
+        ident: String,
+        label: String,
+    },
+}
+
+impl LiveLinkTarget {
+    fn path(&self) -> &Path {
+        match self {
+            LiveLinkTarget::ShroudFile(path) => path,
+            LiveLinkTarget::Block { path, .. } => path,
+        }
+    }
+
+    fn name(&self) -> String {
+        let file_name = self
+            .path()
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match self {
+            LiveLinkTarget::ShroudFile(_) => file_name,
+            LiveLinkTarget::Block { label, .. } => format!("{label} in {file_name}"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LiveLinkMessage {
+    #[error("Wrote {0}.")]
+    Written(String),
+
+    #[error("Failed to write {0}: {1}")]
+    Failed(String, String),
+
+    #[error("Unlinked from {0}, as another shroud was loaded into this tab.")]
+    Unlinked(String),
+}
+
+#[derive(Default)]
+pub struct LiveLink {
+    message_option: Option<LiveLinkMessage>,
+    /// What was last written and where, so an edit that changes nothing in the export writes nothing.
+    last_written_option: Option<(LiveLinkTarget, String)>,
+}
+
+impl ShroudEditor {
+    pub fn live_link_settings(&mut self, ui: &mut Ui) {
+        CollapsingState::load_with_default_open(ui.ctx(), "live_link".into(), false)
+            .show_header(ui, |ui| {
+                ui.strong("Live Link");
+            })
+            .body(|ui| {
+                ui.label("Writes the shroud to a file after every edit, for the game to reload.");
+                ui.horizontal(|ui| {
+                    if ui.button("Link to Shroud File").clicked() {
+                        self.save_file_dialog(WhichFileDialog::LiveLinkShroudFile, "shroud.lua");
+                    }
+                    let loaded_block_option =
+                        self.blocks_file_option.as_ref().and_then(|blocks_file| {
+                            let block = &blocks_file.blocks[blocks_file.loaded_block_idx_option?];
+                            Some((
+                                blocks_file.path.clone(),
+                                block.ident.clone()?,
+                                block.label(),
+                            ))
+                        });
+                    if ui
+                        .add_enabled(
+                            loaded_block_option.is_some(),
+                            Button::new("Link to Loaded Block"),
+                        )
+                        .on_disabled_hover_text(
+                            "Load a block with an ident from a blocks.lua first.",
+                        )
+                        .clicked()
+                        && let Some((path, ident, label)) = loaded_block_option
+                    {
+                        self.set_live_link_target(LiveLinkTarget::Block { path, ident, label });
+                    }
+                });
+                let linked_option = self
+                    .live_link_target_option
+                    .as_ref()
+                    .map(|live_link_target| {
+                        (
+                            live_link_target.name(),
+                            live_link_target.path().display().to_string(),
+                        )
+                    });
+                if let Some((name, path)) = linked_option {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Linked to {name}")).on_hover_text(path);
+                        if ui.button("Unlink").clicked() {
+                            self.live_link_target_option = None;
+                            self.live_link.message_option = None;
+                        }
+                    });
+                }
+                if let Some(message) = &self.live_link.message_option {
+                    ui.label(message.to_string());
+                }
+            });
+    }
+
+    /// Links the current tab and writes to the target straight away.
+    pub fn set_live_link_target(&mut self, live_link_target: LiveLinkTarget) {
+        self.live_link_target_option = Some(live_link_target);
+        self.live_link.last_written_option = None;
+        self.live_link_logic();
+    }
+
+    /// Called when another shroud is loaded into the current tab, so it is not written over the
+    /// target. The link is kept if `is_loaded_from_target` says the shroud came from the target.
+    pub fn unlink_live_link_on_load(
+        &mut self,
+        is_loaded_from_target: impl FnOnce(&LiveLinkTarget) -> bool,
+    ) {
+        if let Some(live_link_target) = &self.live_link_target_option
+            && !is_loaded_from_target(live_link_target)
+        {
+            self.live_link.message_option =
+                Some(LiveLinkMessage::Unlinked(live_link_target.name()));
+            self.live_link_target_option = None;
+        }
+    }
+
+    /// Called whenever an edit is committed to the undo history, and on undo and redo.
+    pub fn live_link_logic(&mut self) {
+        let Some(live_link_target) = self.live_link_target_option.clone() else {
+            return;
+        };
+        let shroud_export = self.export_shroud();
+        if self
+            .live_link
+            .last_written_option
+            .as_ref()
+            .is_some_and(|(last_target, last_export)| {
+                *last_target == live_link_target && *last_export == shroud_export
+            })
+        {
+            return;
+        }
+        let result = match &live_link_target {
+            LiveLinkTarget::ShroudFile(path) => {
+                write_file_atomically(path, &shroud_export).map_err(|err| err.to_string())
+            }
+            LiveLinkTarget::Block { path, ident, .. } => {
+                write_block_shroud_to_file(path, ident, &shroud_export)
+                    .map(|(text, blocks, _)| {
+                        if let Some(blocks_file) = &mut self.blocks_file_option
+                            && blocks_file.path == *path
+                        {
+                            blocks_file.text = text;
+                            blocks_file.blocks = blocks;
+                        }
+                    })
+                    .map_err(|err| err.to_string())
+            }
+        };
+        self.live_link.message_option = Some(match result {
+            Ok(()) => {
+                self.hot_reload.note_file_written(live_link_target.path());
+                self.live_link.last_written_option =
+                    Some((live_link_target.clone(), shroud_export));
+                LiveLinkMessage::Written(live_link_target.name())
+            }
+            Err(err) => LiveLinkMessage::Failed(live_link_target.name(), err),
+        });
+    }
+}
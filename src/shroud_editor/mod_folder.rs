@@ -0,0 +1,384 @@
+use std::{
+    cmp::Ordering,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use egui::{
+    Color32, ComboBox, Pos2, Rect, Response, ScrollArea, Sense, Shape, Stroke, Ui, Vec2,
+    collapsing_header::CollapsingState,
+};
+use itertools::Itertools;
+use luexks_reassembly::utility::display_oriented_math::do3d_float_from;
+use thiserror::Error;
+
+use crate::{
+    file_import_export::WhichFileDialog,
+    mirror_pairs::MirrorPairs,
+    parse_shapes_text::{ShapesMessage, parse_shapes_text},
+    parse_shroud_text::parse_shroud_text_lenient,
+    shape_container::ShapeContainer,
+    shroud_editor::{
+        ShroudEditor,
+        blocks_file::{BlocksFile, read_blocks_file},
+        parse_blocks_text::BlockEntry,
+        preferences::RecentFileKind,
+        render_shroud::get_block_as_shroud_layer_container,
+    },
+    styles::BACKGROUND_COLOUR,
+};
+
+const SHAPES_FILE_NAME: &str = "shapes.lua";
+const BLOCKS_FILE_NAME: &str = "blocks.lua";
+const THUMBNAIL_SIZE: f32 = 48.0;
+
+type ParsedShapes = (Vec<ShapeContainer>, MirrorPairs, Vec<usize>);
+/// The shapes file's path and text, its parsed shapes, and the blocks file.
+type ModFolderFiles = (Option<PathBuf>, String, ParsedShapes, BlocksFile);
+
+#[derive(Error, Debug)]
+pub enum ModFolderMessage {
+    #[error("Found {0} blocks.")]
+    Opened(usize),
+
+    #[error("No blocks.lua in this folder :(")]
+    NoBlocksFile,
+
+    #[error("Failed to load shapes.lua: {0}")]
+    Shapes(String),
+
+    #[error("Failed to load blocks.lua: {0}")]
+    Blocks(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ShroudFilter {
+    All,
+    WithShroud,
+    WithoutShroud,
+}
+
+/// The block and each shroud layer, lowest first, as outlines relative to the block.
+type Thumbnail = Vec<(Vec<Pos2>, Color32, Color32)>;
+
+pub struct ModFolder {
+    pub path: PathBuf,
+    shapes_path_option: Option<PathBuf>,
+    shroud_filter: ShroudFilter,
+    shape_filter_option: Option<String>,
+    thumbnails: Vec<Thumbnail>,
+    /// The blocks file text the thumbnails were made from, so they are remade after it is written to.
+    thumbnails_blocks_text: String,
+}
+
+/// Finds and parses the folder's shapes.lua, if it has one, and its blocks.lua.
+fn read_mod_folder(path: &Path) -> Result<ModFolderFiles, ModFolderMessage> {
+    let shapes_path_option = find_mod_file(path, SHAPES_FILE_NAME);
+    let (shapes_text, parsed_shapes) = match &shapes_path_option {
+        Some(shapes_path) => {
+            let shapes_text = read_to_string(shapes_path).map_err(|_| {
+                ModFolderMessage::Shapes(ShapesMessage::CouldNotOpenFile.to_string())
+            })?;
+            let parsed_shapes = parse_shapes_text(&shapes_text)
+                .map_err(|err| ModFolderMessage::Shapes(err.to_string()))?;
+            (shapes_text, parsed_shapes)
+        }
+        None => (String::new(), (Vec::new(), Vec::new(), Vec::new())),
+    };
+    let blocks_path =
+        find_mod_file(path, BLOCKS_FILE_NAME).ok_or(ModFolderMessage::NoBlocksFile)?;
+    let blocks_file =
+        read_blocks_file(blocks_path).map_err(|err| ModFolderMessage::Blocks(err.to_string()))?;
+    Ok((shapes_path_option, shapes_text, parsed_shapes, blocks_file))
+}
+
+/// Looks in the folder itself, then in the folders directly inside it.
+fn find_mod_file(mod_folder_path: &Path, file_name: &str) -> Option<PathBuf> {
+    let path = mod_folder_path.join(file_name);
+    if path.is_file() {
+        return Some(path);
+    }
+    read_dir(mod_folder_path)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path().join(file_name))
+        .sorted()
+        .find(|path| path.is_file())
+}
+
+impl ShroudEditor {
+    pub fn mod_folder_settings(&mut self, ui: &mut Ui) {
+        CollapsingState::load_with_default_open(ui.ctx(), "mod_folder".into(), false)
+            .show_header(ui, |ui| {
+                ui.strong("Mod Folder");
+            })
+            .body(|ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Open Mod Folder").clicked() {
+                        self.which_file_import = WhichFileDialog::ModFolderOpen;
+                        self.file_dialog.pick_directory();
+                    }
+                    if let Some(message) = &self.mod_folder_message_option {
+                        ui.label(message.to_string());
+                    }
+                });
+                let Some(mod_folder) = &self.mod_folder_option else {
+                    return;
+                };
+                ui.small(mod_folder.path.display().to_string());
+                ui.small(match &mod_folder.shapes_path_option {
+                    Some(shapes_path) => format!("Shapes from {}", shapes_path.display()),
+                    None => "No shapes.lua, so only vanilla shapes are loaded.".to_string(),
+                });
+                self.mod_folder_thumbnails_logic();
+                self.mod_folder_filters(ui);
+                self.mod_folder_blocks(ui);
+                if let Some(message) = &self.blocks_file_message_option {
+                    ui.label(message.to_string());
+                }
+            });
+    }
+
+    /// Loads the folder's custom shapes and its blocks once both have been read, so a folder that
+    /// fails to open changes nothing. Custom shapes from before are unloaded even if the folder
+    /// has none.
+    pub fn open_mod_folder(&mut self, path: PathBuf) {
+        let (
+            shapes_path_option,
+            shapes_text,
+            (imported_shapes, mirror_pairs, non_mirrors),
+            blocks_file,
+        ) = match read_mod_folder(&path) {
+            Ok(mod_folder_files) => mod_folder_files,
+            Err(err) => {
+                self.mod_folder_message_option = Some(err);
+                return;
+            }
+        };
+        self.load_shapes(imported_shapes, mirror_pairs, non_mirrors);
+        self.loaded_shapes_text = shapes_text;
+        self.shapes_import_path_option = shapes_path_option.clone();
+        self.mod_folder_message_option = Some(ModFolderMessage::Opened(blocks_file.blocks.len()));
+        self.set_blocks_file(blocks_file);
+        self.add_recent_file(RecentFileKind::ModFolder, path.clone());
+        self.mod_folder_option = Some(ModFolder {
+            path,
+            shapes_path_option,
+            shroud_filter: ShroudFilter::All,
+            shape_filter_option: None,
+            thumbnails: Vec::new(),
+            thumbnails_blocks_text: String::new(),
+        });
+    }
+
+    fn mod_folder_thumbnails_logic(&mut self) {
+        let (Some(mod_folder), Some(blocks_file)) =
+            (&self.mod_folder_option, &self.blocks_file_option)
+        else {
+            return;
+        };
+        if mod_folder.thumbnails_blocks_text == blocks_file.text
+            && mod_folder.thumbnails.len() == blocks_file.blocks.len()
+        {
+            return;
+        }
+        let thumbnails = blocks_file
+            .blocks
+            .iter()
+            .map(|block| self.block_thumbnail(blocks_file, block))
+            .collect();
+        let blocks_text = blocks_file.text.clone();
+        if let Some(mod_folder) = &mut self.mod_folder_option {
+            mod_folder.thumbnails = thumbnails;
+            mod_folder.thumbnails_blocks_text = blocks_text;
+        }
+    }
+
+    /// Forgets the thumbnails, so they are remade with the shapes that are loaded now.
+    pub fn invalidate_mod_folder_thumbnails(&mut self) {
+        if let Some(mod_folder) = &mut self.mod_folder_option {
+            mod_folder.thumbnails.clear();
+        }
+    }
+
+    fn block_thumbnail(&self, blocks_file: &BlocksFile, block: &BlockEntry) -> Thumbnail {
+        let Ok(block_container) = self.block_container_from_block_entry(block) else {
+            return Vec::new();
+        };
+        let mut shroud = block
+            .shroud_span_option
+            .clone()
+            .map(|shroud_span| {
+                parse_shroud_text_lenient(&blocks_file.text[shroud_span], &self.loaded_shapes).0
+            })
+            .unwrap_or_default();
+        shroud.iter_mut().for_each(|shroud_layer| {
+            let offset = shroud_layer.shroud_layer.offset.as_ref().unwrap();
+            shroud_layer.shroud_layer.offset = Some(do3d_float_from(
+                offset.x.to_f32() + block_container.offset.x,
+                offset.y.to_f32() + block_container.offset.y,
+                offset.z.to_f32(),
+            ));
+        });
+        shroud.push(get_block_as_shroud_layer_container(&block_container));
+        shroud
+            .iter()
+            .sorted_by(|shroud_layer_1, shroud_layer_2| {
+                let z1 = shroud_layer_1
+                    .shroud_layer
+                    .offset
+                    .as_ref()
+                    .unwrap()
+                    .z
+                    .to_f32();
+                let z2 = shroud_layer_2
+                    .shroud_layer
+                    .offset
+                    .as_ref()
+                    .unwrap()
+                    .z
+                    .to_f32();
+                z1.partial_cmp(&z2).unwrap_or(Ordering::Equal)
+            })
+            .map(|shroud_layer| {
+                (
+                    shroud_layer.apply_offset_to_verts(shroud_layer.get_shroud_layer_vertices()),
+                    block_container
+                        .get_shroud_color(shroud_layer.shroud_layer.color_1.unwrap())
+                        .into(),
+                    block_container
+                        .get_shroud_color(shroud_layer.shroud_layer.line_color.unwrap())
+                        .into(),
+                )
+            })
+            .collect()
+    }
+
+    fn mod_folder_filters(&mut self, ui: &mut Ui) {
+        let Some(blocks_file) = &self.blocks_file_option else {
+            return;
+        };
+        let shapes = blocks_file
+            .blocks
+            .iter()
+            .map(|block| block.shape.clone().unwrap_or("SQUARE".to_string()))
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+        let Some(mod_folder) = &mut self.mod_folder_option else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut mod_folder.shroud_filter, ShroudFilter::All, "All");
+            ui.radio_value(
+                &mut mod_folder.shroud_filter,
+                ShroudFilter::WithShroud,
+                "Has shroud",
+            );
+            ui.radio_value(
+                &mut mod_folder.shroud_filter,
+                ShroudFilter::WithoutShroud,
+                "No shroud",
+            );
+        });
+        ComboBox::from_id_salt("mod_folder_shape_filter")
+            .selected_text(
+                mod_folder
+                    .shape_filter_option
+                    .as_deref()
+                    .unwrap_or("Any shape"),
+            )
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut mod_folder.shape_filter_option, None, "Any shape");
+                shapes.into_iter().for_each(|shape| {
+                    let text = shape.clone();
+                    ui.selectable_value(&mut mod_folder.shape_filter_option, Some(shape), text);
+                });
+            });
+    }
+
+    fn mod_folder_blocks(&mut self, ui: &mut Ui) {
+        let (Some(mod_folder), Some(blocks_file)) =
+            (&self.mod_folder_option, &self.blocks_file_option)
+        else {
+            return;
+        };
+        let mut block_idx_to_open_option = None;
+        ScrollArea::vertical()
+            .id_salt("mod_folder_blocks")
+            .max_height(400.0)
+            .show(ui, |ui| {
+                blocks_file
+                    .blocks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, block)| match mod_folder.shroud_filter {
+                        ShroudFilter::All => true,
+                        ShroudFilter::WithShroud => block.shroud_span_option.is_some(),
+                        ShroudFilter::WithoutShroud => block.shroud_span_option.is_none(),
+                    })
+                    .filter(|(_, block)| {
+                        mod_folder
+                            .shape_filter_option
+                            .as_ref()
+                            .is_none_or(|shape| block.shape.as_deref().unwrap_or("SQUARE") == shape)
+                    })
+                    .for_each(|(block_idx, block)| {
+                        ui.horizontal(|ui| {
+                            let thumbnail_response = thumbnail(
+                                ui,
+                                mod_folder
+                                    .thumbnails
+                                    .get(block_idx)
+                                    .map(Vec::as_slice)
+                                    .unwrap_or_default(),
+                            );
+                            let is_loaded = blocks_file.loaded_block_idx_option == Some(block_idx);
+                            let label_response = ui.selectable_label(is_loaded, block.label());
+                            let response = thumbnail_response.union(label_response);
+                            if response.clicked() {
+                                block_idx_to_open_option = Some((block_idx, false));
+                            }
+                            response.context_menu(|ui| {
+                                if ui.button("Open in New Tab").clicked() {
+                                    block_idx_to_open_option = Some((block_idx, true));
+                                }
+                            });
+                        });
+                    });
+            });
+        if let Some((block_idx, in_new_tab)) = block_idx_to_open_option {
+            self.open_block_from_blocks_file(block_idx, in_new_tab);
+        }
+    }
+}
+
+fn thumbnail(ui: &mut Ui, thumbnail: &[(Vec<Pos2>, Color32, Color32)]) -> Response {
+    let (rect, response) = ui.allocate_exact_size(Vec2::splat(THUMBNAIL_SIZE), Sense::click());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, BACKGROUND_COLOUR);
+    let bounds = Rect::from_points(
+        &thumbnail
+            .iter()
+            .flat_map(|(verts, _, _)| verts.iter().copied())
+            .collect::<Vec<_>>(),
+    );
+    if !bounds.is_finite() {
+        return response;
+    }
+    let scale = (THUMBNAIL_SIZE - 4.0) / bounds.width().max(bounds.height()).max(f32::EPSILON);
+    thumbnail
+        .iter()
+        .for_each(|(verts, fill_color, line_color)| {
+            // Concave shapes are filled a little wrong, which is fine at this size.
+            painter.add(Shape::convex_polygon(
+                verts
+                    .iter()
+                    .map(|vert| rect.center() + (*vert - bounds.center()) * scale)
+                    .collect(),
+                *fill_color,
+                Stroke::new(1.0, *line_color),
+            ));
+        });
+    response
+}
@@ -0,0 +1,141 @@
+use egui::{Checkbox, Color32, DragValue, Pos2, Rect, Stroke, Ui, pos2, vec2};
+
+use crate::{
+    object_snap::{object_snap, snap_points},
+    shroud_editor::{
+        ShroudEditor, render_shroud::get_block_as_shroud_layer_container,
+        shroud_layer_moving::move_selection_to,
+    },
+    shroud_interaction::ShroudInteraction,
+    shroud_layer_container::ShroudLayerContainer,
+};
+
+const OBJECT_SNAP_INDICATOR_RADIUS: f32 = 6.0;
+const OBJECT_SNAP_INDICATOR_COLOUR: Color32 = Color32::from_rgb(255, 140, 0);
+
+fn world_vertices(shroud_layer: &ShroudLayerContainer) -> Vec<Pos2> {
+    shroud_layer.apply_offset_to_verts(shroud_layer.get_shroud_layer_vertices())
+}
+
+fn world_centre(shroud_layer: &ShroudLayerContainer) -> Pos2 {
+    let offset = shroud_layer.shroud_layer.offset.as_ref().unwrap();
+    pos2(offset.x.to_f32(), -offset.y.to_f32())
+}
+
+impl ShroudEditor {
+    pub fn object_snap_settings(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Object Snap Enabled:");
+            ui.add(Checkbox::new(&mut self.object_snap_enabled, ""));
+            if self.object_snap_enabled {
+                ui.label("Distance:");
+                ui.add(
+                    DragValue::new(&mut self.object_snap_distance)
+                        .speed(0.5)
+                        .range(1.0..=64.0)
+                        .suffix(" px"),
+                );
+            }
+        })
+        .response
+        .on_hover_text(
+            "Dragged layers snap their corners, edge midpoints and centre to the corners and edges of other layers and the block.",
+        );
+    }
+
+    /// Runs after the layers being dragged or placed have been moved to the grid, and moves them on
+    /// to the nearest corner or edge in reach.
+    pub fn object_snap_logic(&mut self) {
+        self.object_snap_pos_option = None;
+        if !self.object_snap_enabled {
+            return;
+        }
+        let (ShroudInteraction::Dragging {
+            selection,
+            potentially_snapped_drag_pos,
+            ..
+        }
+        | ShroudInteraction::Placing {
+            selection,
+            potentially_snapped_drag_pos,
+            ..
+        }) = &mut self.shroud_interaction
+        else {
+            return;
+        };
+        let moving_idxs = selection
+            .0
+            .iter()
+            .flat_map(|moving_shroud_layer| {
+                [
+                    Some(moving_shroud_layer.idx),
+                    self.shroud[moving_shroud_layer.idx].mirror_index_option,
+                ]
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        // A selected layer whose mirror is selected after it ends up mirrored rather than moved
+        // with the pointer, so its points would snap the wrong way.
+        let moving_points = selection
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(selection_idx, moving_shroud_layer)| {
+                !selection.0[selection_idx + 1..].iter().any(|later| {
+                    self.shroud[later.idx].mirror_index_option == Some(moving_shroud_layer.idx)
+                })
+            })
+            .flat_map(|(_, moving_shroud_layer)| {
+                let shroud_layer = &self.shroud[moving_shroud_layer.idx];
+                snap_points(&world_vertices(shroud_layer), world_centre(shroud_layer))
+            })
+            .collect::<Vec<_>>();
+        let mut outlines = self
+            .shroud
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !moving_idxs.contains(idx))
+            .map(|(_, shroud_layer)| world_vertices(shroud_layer))
+            .collect::<Vec<_>>();
+        if self.block_container.visible {
+            outlines.push(world_vertices(&get_block_as_shroud_layer_container(
+                &self.block_container,
+            )));
+        }
+
+        let Some((shift, snap_pos)) = object_snap(
+            &moving_points,
+            &outlines,
+            self.object_snap_distance / self.zoom,
+        ) else {
+            return;
+        };
+        *potentially_snapped_drag_pos += vec2(shift.x, -shift.y);
+        move_selection_to(*potentially_snapped_drag_pos, selection, &mut self.shroud);
+        self.object_snap_pos_option = Some(snap_pos);
+    }
+
+    pub fn object_snap_indicator(&self, ui: &mut Ui, rect: Rect) {
+        let Some(snap_pos) = self.object_snap_pos_option else {
+            return;
+        };
+        let screen_pos = self.world_pos_to_screen_pos(snap_pos, rect);
+        let stroke = Stroke::new(2.0, OBJECT_SNAP_INDICATOR_COLOUR);
+        let painter = ui.painter();
+        painter.circle_stroke(screen_pos, OBJECT_SNAP_INDICATOR_RADIUS, stroke);
+        painter.line_segment(
+            [
+                screen_pos - vec2(OBJECT_SNAP_INDICATOR_RADIUS, 0.0),
+                screen_pos + vec2(OBJECT_SNAP_INDICATOR_RADIUS, 0.0),
+            ],
+            stroke,
+        );
+        painter.line_segment(
+            [
+                screen_pos - vec2(0.0, OBJECT_SNAP_INDICATOR_RADIUS),
+                screen_pos + vec2(0.0, OBJECT_SNAP_INDICATOR_RADIUS),
+            ],
+            stroke,
+        );
+    }
+}
@@ -0,0 +1,402 @@
+use std::ops::Range;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BlocksMessage {
+    #[error("YES!")]
+    Success,
+
+    #[error("Wrote shroud to block `{0}`.")]
+    Written(String),
+
+    #[error("Could not open file :(")]
+    CouldNotOpenFile,
+
+    #[error("Could not write file :(")]
+    CouldNotWriteFile,
+
+    #[error("Failed to find the outer {{ of the blocks file :(")]
+    Blocks,
+
+    #[error("Unbalanced braces starting at byte {0} :(")]
+    Unbalanced(usize),
+
+    #[error("Failed to find block `{0}` in the blocks file anymore :(")]
+    BlockNotFound(String),
+
+    #[error("More than one block has the ident `{0}`, so none was written :(")]
+    DuplicateIdent(String),
+
+    #[error("Block `{0}` has no ident to find it by :(")]
+    NoIdent(String),
+
+    #[error("Failed to find block shape in loaded shape list: `{0}` :(")]
+    Shape(String),
+
+    #[error("Failed to load the block's shroud: {0}")]
+    Shroud(String),
+}
+
+/// A block table found in a blocks.lua file. Spans are byte ranges into the text that was scanned,
+/// so the file can be patched without touching anything outside them.
+#[derive(Clone, Debug)]
+pub struct BlockEntry {
+    pub span: Range<usize>,
+    pub ident: Option<String>,
+    pub name: Option<String>,
+    pub shape: Option<String>,
+    pub scale: Option<String>,
+    pub fill_color: Option<String>,
+    pub fill_color_1: Option<String>,
+    pub line_color: Option<String>,
+    pub shroud_span_option: Option<Range<usize>>,
+    pub last_field_span_option: Option<Range<usize>>,
+}
+
+impl BlockEntry {
+    pub fn label(&self) -> String {
+        match (&self.ident, &self.name) {
+            (Some(ident), Some(name)) => format!("{ident} {name}"),
+            (Some(ident), None) => ident.clone(),
+            (None, Some(name)) => name.clone(),
+            (None, None) => format!("@{}", self.span.start),
+        }
+    }
+}
+
+pub fn parse_blocks_text(input: &str) -> Result<Vec<BlockEntry>, BlocksMessage> {
+    let bytes = input.as_bytes();
+    let mut i = skip_ws(bytes, 0);
+    if bytes.get(i) != Some(&b'{') {
+        return Err(BlocksMessage::Blocks);
+    }
+    i += 1;
+    let mut blocks = Vec::new();
+    loop {
+        i = skip_ws(bytes, i);
+        match bytes.get(i) {
+            None => return Err(BlocksMessage::Unbalanced(0)),
+            Some(b'}') => break,
+            Some(b'{') => {
+                let end = table_end(bytes, i)?;
+                blocks.push(block_entry(input, i..end)?);
+                i = end;
+            }
+            Some(_) => i = value_end(bytes, i)?,
+        }
+    }
+    Ok(blocks)
+}
+
+/// Replaces the `shroud={...}` assignment of `block`, or adds one on its own line before the
+/// block's closing brace. Nothing outside the replaced or inserted text is changed.
+pub fn replace_block_shroud(input: &str, block: &BlockEntry, shroud_text: &str) -> String {
+    match &block.shroud_span_option {
+        Some(shroud_span) => format!(
+            "{}{}{}",
+            &input[..shroud_span.start],
+            shroud_text,
+            &input[shroud_span.end..]
+        ),
+        None => {
+            let bytes = input.as_bytes();
+            let close_at = block.span.end - 1;
+            let close_line_start = line_start(bytes, close_at);
+            let close_indent = line_indent(input, close_line_start);
+            let field_indent = match &block.last_field_span_option {
+                Some(last_field_span)
+                    if line_start(bytes, last_field_span.start)
+                        > line_start(bytes, block.span.start) =>
+                {
+                    line_indent(input, line_start(bytes, last_field_span.start)).to_string()
+                }
+                _ => format!("{close_indent}    "),
+            };
+            let separator_at_option = block
+                .last_field_span_option
+                .as_ref()
+                .filter(|last_field_span| !separator_follows(bytes, last_field_span.end))
+                .map(|last_field_span| last_field_span.end);
+            let (insert_at, insertion) = if input[close_line_start..close_at].trim().is_empty() {
+                (close_line_start, format!("{field_indent}{shroud_text},\n"))
+            } else {
+                (
+                    close_at,
+                    format!("\n{field_indent}{shroud_text},\n{close_indent}"),
+                )
+            };
+            match separator_at_option {
+                Some(separator_at) => format!(
+                    "{},{}{insertion}{}",
+                    &input[..separator_at],
+                    &input[separator_at..insert_at],
+                    &input[insert_at..]
+                ),
+                None => format!("{}{insertion}{}", &input[..insert_at], &input[insert_at..]),
+            }
+        }
+    }
+}
+
+fn line_start(bytes: &[u8], i: usize) -> usize {
+    bytes[..i]
+        .iter()
+        .rposition(|c| *c == b'\n')
+        .map_or(0, |newline| newline + 1)
+}
+
+fn line_indent(input: &str, line_start: usize) -> &str {
+    let line = &input[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Whether a `,` or `;` comes after `i` before the next field, skipping whitespace and comments.
+fn separator_follows(bytes: &[u8], mut i: usize) -> bool {
+    loop {
+        match bytes.get(i) {
+            Some(b',' | b';') => return true,
+            Some(c) if c.is_ascii_whitespace() => i += 1,
+            Some(b'-') if bytes.get(i + 1) == Some(&b'-') => {
+                if bytes[i + 2..].starts_with(b"[[") {
+                    i = find(bytes, i + 4, b"]]").map_or(bytes.len(), |end| end + 2);
+                } else {
+                    i = find(bytes, i, b"\n").unwrap_or(bytes.len());
+                }
+            }
+            _ => return false,
+        }
+    }
+}
+
+fn block_entry(input: &str, span: Range<usize>) -> Result<BlockEntry, BlocksMessage> {
+    let bytes = input.as_bytes();
+    let mut block = BlockEntry {
+        span: span.clone(),
+        ident: None,
+        name: None,
+        shape: None,
+        scale: None,
+        fill_color: None,
+        fill_color_1: None,
+        line_color: None,
+        shroud_span_option: None,
+        last_field_span_option: None,
+    };
+    let mut i = span.start + 1;
+    let mut is_first_field = true;
+    loop {
+        i = skip_ws(bytes, i);
+        if i >= span.end - 1 {
+            break;
+        }
+        let field_start = i;
+        let key_end = identifier_end(bytes, i);
+        let after_key = skip_ws(bytes, key_end);
+        if key_end > i && bytes.get(after_key) == Some(&b'=') {
+            let key = &input[i..key_end];
+            let value_start = skip_ws(bytes, after_key + 1);
+            let end = value_end(bytes, value_start)?;
+            let value = input[value_start..end].to_string();
+            match key {
+                "ident" => block.ident = Some(value),
+                "name" => block.name = Some(value.trim_matches(['"', '\'']).to_string()),
+                "shape" => block.shape = Some(value),
+                "scale" => block.scale = Some(value),
+                "fillColor" => block.fill_color = Some(value),
+                "fillColor1" => block.fill_color_1 = Some(value),
+                "lineColor" => block.line_color = Some(value),
+                "shroud" => block.shroud_span_option = Some(field_start..end),
+                _ => {}
+            }
+            block.last_field_span_option = Some(field_start..end);
+            i = end;
+        } else {
+            let end = value_end(bytes, i)?;
+            if is_first_field && block.ident.is_none() {
+                block.ident = Some(input[i..end].to_string());
+            }
+            block.last_field_span_option = Some(field_start..end);
+            i = end;
+        }
+        is_first_field = false;
+    }
+    Ok(block)
+}
+
+fn skip_ws(bytes: &[u8], mut i: usize) -> usize {
+    loop {
+        match bytes.get(i) {
+            Some(c) if c.is_ascii_whitespace() || *c == b',' || *c == b';' => i += 1,
+            Some(b'-') if bytes.get(i + 1) == Some(&b'-') => {
+                if bytes[i + 2..].starts_with(b"[[") {
+                    i = find(bytes, i + 4, b"]]").map_or(bytes.len(), |end| end + 2);
+                } else {
+                    i = find(bytes, i, b"\n").unwrap_or(bytes.len());
+                }
+            }
+            _ => return i,
+        }
+    }
+}
+
+fn identifier_end(bytes: &[u8], mut i: usize) -> usize {
+    while bytes
+        .get(i)
+        .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+    {
+        i += 1;
+    }
+    i
+}
+
+fn value_end(bytes: &[u8], i: usize) -> Result<usize, BlocksMessage> {
+    match bytes.get(i) {
+        Some(b'{') => table_end(bytes, i),
+        Some(quote @ (b'"' | b'\'')) => string_end(bytes, i, *quote),
+        Some(b'[') if bytes.get(i + 1) == Some(&b'[') => long_string_end(bytes, i),
+        _ => {
+            let mut end = i;
+            while bytes
+                .get(end)
+                .is_some_and(|c| !c.is_ascii_whitespace() && ![b',', b';', b'{', b'}'].contains(c))
+            {
+                end += 1;
+            }
+            Ok(end.max(i + 1))
+        }
+    }
+}
+
+fn table_end(bytes: &[u8], start: usize) -> Result<usize, BlocksMessage> {
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        i = skip_ws(bytes, i);
+        match bytes.get(i) {
+            Some(b'{') => {
+                depth += 1;
+                i += 1;
+            }
+            Some(b'}') => {
+                depth -= 1;
+                i += 1;
+                if depth == 0 {
+                    return Ok(i);
+                }
+            }
+            Some(quote @ (b'"' | b'\'')) => i = string_end(bytes, i, *quote)?,
+            Some(b'[') if bytes.get(i + 1) == Some(&b'[') => i = long_string_end(bytes, i)?,
+            Some(_) => i += 1,
+            None => break,
+        }
+    }
+    Err(BlocksMessage::Unbalanced(start))
+}
+
+fn string_end(bytes: &[u8], start: usize, quote: u8) -> Result<usize, BlocksMessage> {
+    let mut i = start + 1;
+    while let Some(c) = bytes.get(i) {
+        match *c {
+            b'\\' => i += 2,
+            c if c == quote => return Ok(i + 1),
+            _ => i += 1,
+        }
+    }
+    Err(BlocksMessage::Unbalanced(start))
+}
+
+/// The end of a `[[...]]` string, which may hold braces and quotes that mean nothing.
+fn long_string_end(bytes: &[u8], start: usize) -> Result<usize, BlocksMessage> {
+    find(bytes, start + 2, b"]]")
+        .map(|end| end + 2)
+        .ok_or(BlocksMessage::Unbalanced(start))
+}
+
+fn find(bytes: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+    bytes
+        .get(start..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| start + position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(input: &str, block_idx: usize, shroud_text: &str) -> String {
+        let blocks = parse_blocks_text(input).unwrap();
+        let output = replace_block_shroud(input, &blocks[block_idx], shroud_text);
+        let reparsed_blocks = parse_blocks_text(&output).unwrap();
+        let shroud_span = reparsed_blocks[block_idx]
+            .shroud_span_option
+            .clone()
+            .unwrap();
+        assert_eq!(&output[shroud_span], shroud_text);
+        output
+    }
+
+    #[test]
+    fn replaces_existing_shroud() {
+        let input = "{\n\t{ident=1, shroud={{offset={0,0,0}}}, name=\"a\"},\n\t{ident=2}\n}\n";
+        assert_eq!(
+            replace(input, 0, "shroud={{taper=0.5}}"),
+            "{\n\t{ident=1, shroud={{taper=0.5}}, name=\"a\"},\n\t{ident=2}\n}\n"
+        );
+    }
+
+    #[test]
+    fn inserts_separator_after_last_field() {
+        let input = "{\n\t{\n\t\tident=1,\n\t\tname=\"a\" -- no comma\n\t}\n}";
+        assert_eq!(
+            replace(input, 0, "shroud={}"),
+            "{\n\t{\n\t\tident=1,\n\t\tname=\"a\", -- no comma\n\t\tshroud={},\n\t}\n}"
+        );
+    }
+
+    #[test]
+    fn keeps_existing_separator() {
+        let input = "{\n\t{\n\t\tident=1;\n\t}\n}";
+        assert_eq!(
+            replace(input, 0, "shroud={}"),
+            "{\n\t{\n\t\tident=1;\n\t\tshroud={},\n\t}\n}"
+        );
+    }
+
+    #[test]
+    fn inserts_into_block_on_one_line() {
+        let input = "{\n\t{ident=1, name=\"a\"}\n}";
+        assert_eq!(
+            replace(input, 0, "shroud={}"),
+            "{\n\t{ident=1, name=\"a\",\n\t    shroud={},\n\t}\n}"
+        );
+    }
+
+    #[test]
+    fn braces_in_comments_and_strings() {
+        let input = "{\n\t{ident=1, -- }\n\t\tname=[[a}b]], desc=\"}{\", --[[ { ]]\n\t\tfeatures=THRUSTER\n\t},\n\t{ident=2}\n}";
+        let blocks = parse_blocks_text(input).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].name.as_deref(), Some("[[a}b]]"));
+        assert_eq!(
+            &input[blocks[0].span.clone()],
+            &input[input.find("{ident=1").unwrap()..input.find("},").unwrap() + 1]
+        );
+        assert_eq!(blocks[1].ident.as_deref(), Some("2"));
+        let output = replace(input, 1, "shroud={}");
+        assert_eq!(
+            &output[..blocks[1].span.start],
+            &input[..blocks[1].span.start]
+        );
+        assert!(output.ends_with("{ident=2,\n\t    shroud={},\n\t}\n}"));
+    }
+
+    #[test]
+    fn unbalanced_block_is_an_error() {
+        assert!(matches!(
+            parse_blocks_text("{\n\t{ident=1, name=[[a}\n}"),
+            Err(BlocksMessage::Unbalanced(_))
+        ));
+    }
+}
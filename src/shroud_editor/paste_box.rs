@@ -0,0 +1,106 @@
+use std::ops::Range;
+
+use egui::{
+    Align, Color32, Response, ScrollArea, Stroke, TextBuffer, TextEdit, Ui,
+    text::{CCursor, LayoutJob, LayoutSection},
+};
+use egui_extras::syntax_highlighting::{CodeTheme, highlight};
+
+use crate::source_location::SourceLocation;
+
+const ERROR_BACKGROUND_COLOUR: Color32 = Color32::from_rgb(255, 200, 200);
+
+pub struct PasteBoxError {
+    location: SourceLocation,
+    is_scrolled_to: bool,
+}
+
+impl PasteBoxError {
+    pub fn new(location: SourceLocation) -> PasteBoxError {
+        PasteBoxError {
+            location,
+            is_scrolled_to: false,
+        }
+    }
+}
+
+/// The code editor used by the import paste boxes. A parse error is marked, and scrolled to once,
+/// until the text is edited.
+pub fn paste_box_text_edit(
+    ui: &mut Ui,
+    text: &mut String,
+    error_option: &mut Option<PasteBoxError>,
+) -> Response {
+    ScrollArea::horizontal()
+        .show(ui, |ui| {
+            let theme = CodeTheme::light(12.0);
+            let error_range_option = error_option
+                .as_ref()
+                .map(|error| error.location.range.clone());
+            let mut layouter = |ui: &Ui, buf: &dyn TextBuffer, wrap_width: f32| {
+                let mut layout_job = highlight(ui.ctx(), ui.style(), &theme, buf.as_str(), "toml");
+                if let Some(error_range) = &error_range_option {
+                    mark_error_range(&mut layout_job, error_range.clone());
+                }
+                layout_job.wrap.max_width = wrap_width;
+                ui.fonts_mut(|f| f.layout_job(layout_job))
+            };
+            let output = TextEdit::multiline(text)
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .layouter(&mut layouter)
+                .show(ui);
+            if output.response.changed() {
+                *error_option = None;
+            }
+            if let Some(error) = error_option
+                && !error.is_scrolled_to
+                && let Some(before_error) = text.get(..error.location.range.start)
+            {
+                error.is_scrolled_to = true;
+                let error_rect = output
+                    .galley
+                    .pos_from_cursor(CCursor::new(before_error.chars().count()))
+                    .translate(output.galley_pos.to_vec2());
+                ui.scroll_to_rect(error_rect, Some(Align::Center));
+            }
+            output.response
+        })
+        .inner
+}
+
+fn mark_error_range(layout_job: &mut LayoutJob, error_range: Range<usize>) {
+    let sections = std::mem::take(&mut layout_job.sections);
+    for section in sections {
+        let Range { start, end } = section.byte_range.clone();
+        let error_start = error_range.start.clamp(start, end);
+        let error_end = error_range.end.clamp(start, end);
+        if error_start == error_end {
+            layout_job.sections.push(section);
+            continue;
+        }
+        for (byte_range, is_error) in [
+            (start..error_start, false),
+            (error_start..error_end, true),
+            (error_end..end, false),
+        ] {
+            if byte_range.is_empty() {
+                continue;
+            }
+            let mut format = section.format.clone();
+            if is_error {
+                format.background = ERROR_BACKGROUND_COLOUR;
+                format.underline = Stroke::new(1.0, Color32::RED);
+            }
+            layout_job.sections.push(LayoutSection {
+                leading_space: if byte_range.start == start {
+                    section.leading_space
+                } else {
+                    0.0
+                },
+                byte_range,
+                format,
+            });
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::{fmt::Write as _, fs::read_to_string, path::PathBuf};
+
+use egui::{Context, Ui, collapsing_header::CollapsingState};
+
+use crate::{
+    config::{config_file_path, write_config_file},
+    shroud_editor::{ShroudEditor, export_presets::load_export_presets},
+};
+
+const PREFERENCES_FILE_NAME: &str = "preferences";
+const PREFERENCES_FILE_HEADER: &str = "--Luexks Shroud Editor preferences";
+const RECENT_FILES_MAX: usize = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecentFileKind {
+    Project,
+    Shroud,
+    Blocks,
+    ModFolder,
+}
+
+impl RecentFileKind {
+    fn name(&self) -> &'static str {
+        match self {
+            RecentFileKind::Project => "project",
+            RecentFileKind::Shroud => "shroud",
+            RecentFileKind::Blocks => "blocks",
+            RecentFileKind::ModFolder => "mod_folder",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<RecentFileKind> {
+        match name {
+            "project" => Some(RecentFileKind::Project),
+            "shroud" => Some(RecentFileKind::Shroud),
+            "blocks" => Some(RecentFileKind::Blocks),
+            "mod_folder" => Some(RecentFileKind::ModFolder),
+            _ => None,
+        }
+    }
+}
+
+pub type RecentFiles = Vec<(RecentFileKind, PathBuf)>;
+
+impl ShroudEditor {
+    pub fn load_preferences(&mut self) {
+        let Ok(s) = read_to_string(config_file_path(PREFERENCES_FILE_NAME)) else {
+            return;
+        };
+        let preferences = s
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .collect::<Vec<_>>();
+        let get = |name: &str| {
+            preferences
+                .iter()
+                .find(|(preference_name, _)| *preference_name == name)
+                .map(|(_, value)| *value)
+        };
+
+        if let Some(grid_size) = get("grid_size").and_then(|value| value.parse().ok()) {
+            self.settings_grid_size = grid_size;
+            self.grid_size = grid_size;
+        }
+        load_preference(&mut self.grid_visible, get("grid_visible"));
+        load_preference(&mut self.grid_snap_enabled, get("grid_snap_enabled"));
+        load_preference(&mut self.angle_snap, get("angle_snap"));
+        load_preference(&mut self.angle_snap_enabled, get("angle_snap_enabled"));
+        load_preference(&mut self.object_snap_enabled, get("object_snap_enabled"));
+        load_preference(&mut self.object_snap_distance, get("object_snap_distance"));
+        load_preference(
+            &mut self.fill_color_gradient_delta_enabled,
+            get("fill_color_gradient_delta_enabled"),
+        );
+        load_preference(
+            &mut self.only_show_selected_shroud_layers,
+            get("only_show_selected_shroud_layers"),
+        );
+        load_preference(&mut self.hot_reload.enabled, get("hot_reload"));
+        load_preference(
+            self.tool_settings.radial_by_count_mut(),
+            get("tools.radial_by_count"),
+        );
+        self.tool_settings
+            .preference_values_mut()
+            .into_iter()
+            .for_each(|(name, value)| load_preference(value, get(&format!("tools.{name}"))));
+
+        self.recent_files = preferences
+            .iter()
+            .filter(|(name, _)| *name == "recent")
+            .filter_map(|(_, value)| {
+                let (kind, path) = value.split_once(' ')?;
+                let path = path.strip_prefix("[[")?.strip_suffix("]]")?;
+                Some((RecentFileKind::from_name(kind)?, PathBuf::from(path)))
+            })
+            .take(RECENT_FILES_MAX)
+            .collect();
+
+        preferences
+            .iter()
+            .filter_map(|(name, value)| Some((name.strip_prefix("export.")?, *value)))
+            .for_each(|(name, value)| {
+                self.export_format.set_key_value(name, value);
+            });
+        self.export_presets = load_export_presets();
+    }
+
+    pub fn save_preferences(&mut self) {
+        let mut s = String::new();
+        writeln!(s, "{PREFERENCES_FILE_HEADER}").unwrap();
+        writeln!(s, "grid_size={}", self.settings_grid_size).unwrap();
+        writeln!(s, "grid_visible={}", self.grid_visible).unwrap();
+        writeln!(s, "grid_snap_enabled={}", self.grid_snap_enabled).unwrap();
+        writeln!(s, "angle_snap={}", self.angle_snap).unwrap();
+        writeln!(s, "angle_snap_enabled={}", self.angle_snap_enabled).unwrap();
+        writeln!(s, "object_snap_enabled={}", self.object_snap_enabled).unwrap();
+        writeln!(s, "object_snap_distance={}", self.object_snap_distance).unwrap();
+        writeln!(
+            s,
+            "fill_color_gradient_delta_enabled={}",
+            self.fill_color_gradient_delta_enabled
+        )
+        .unwrap();
+        writeln!(
+            s,
+            "only_show_selected_shroud_layers={}",
+            self.only_show_selected_shroud_layers
+        )
+        .unwrap();
+        writeln!(s, "hot_reload={}", self.hot_reload.enabled).unwrap();
+        writeln!(
+            s,
+            "tools.radial_by_count={}",
+            self.tool_settings.radial_by_count_mut()
+        )
+        .unwrap();
+        self.tool_settings
+            .preference_values_mut()
+            .into_iter()
+            .for_each(|(name, value)| writeln!(s, "tools.{name}={value}").unwrap());
+        self.export_format
+            .key_values()
+            .into_iter()
+            .for_each(|(name, value)| writeln!(s, "export.{name}={value}").unwrap());
+        self.recent_files.iter().for_each(|(kind, path)| {
+            writeln!(s, "recent={} [[{}]]", kind.name(), path.display()).unwrap();
+        });
+        let _ = write_config_file(PREFERENCES_FILE_NAME, &s);
+    }
+
+    pub fn preferences_logic(&mut self, ctx: &Context) {
+        if ctx.input(|i| i.viewport().close_requested()) {
+            self.save_preferences();
+        }
+    }
+
+    /// Saved straight away, so the list survives a crash.
+    pub fn add_recent_file(&mut self, kind: RecentFileKind, path: PathBuf) {
+        self.recent_files
+            .retain(|(recent_kind, recent_path)| *recent_kind != kind || *recent_path != path);
+        self.recent_files.insert(0, (kind, path));
+        self.recent_files.truncate(RECENT_FILES_MAX);
+        self.save_preferences();
+    }
+
+    pub fn recent_files_menu(&mut self, ctx: &Context, ui: &mut Ui) {
+        if self.recent_files.is_empty() {
+            return;
+        }
+        let mut opened_option = None;
+        CollapsingState::load_with_default_open(ctx, "recent_files".into(), false)
+            .show_header(ui, |ui| ui.label("Recent Files"))
+            .body_unindented(|ui| {
+                self.recent_files.iter().for_each(|(kind, path)| {
+                    let file_name = path
+                        .file_name()
+                        .map(|file_name| file_name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    if ui
+                        .button(format!("{}: {}", kind.name(), file_name))
+                        .on_hover_text(path.display().to_string())
+                        .clicked()
+                    {
+                        opened_option = Some((*kind, path.clone()));
+                    }
+                });
+            });
+        if let Some((kind, path)) = opened_option {
+            match kind {
+                RecentFileKind::Project => self.open_project_from_file(ctx, path),
+                RecentFileKind::Shroud => self.import_shroud_from_file(path),
+                RecentFileKind::Blocks => self.open_blocks_file(path),
+                RecentFileKind::ModFolder => self.open_mod_folder(path),
+            }
+        }
+    }
+}
+
+fn load_preference<T: std::str::FromStr>(preference: &mut T, value_option: Option<&str>) {
+    if let Some(value) = value_option.and_then(|value| value.parse().ok()) {
+        *preference = value;
+    }
+}
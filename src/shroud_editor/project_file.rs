@@ -0,0 +1,241 @@
+use std::{
+    fmt::Write as _,
+    fs::{File, read_to_string},
+    io::Write,
+    path::PathBuf,
+};
+
+use egui::{Context, Ui};
+use luexks_reassembly::blocks::shroud_layer::ShroudLayerColor;
+
+use crate::{
+    block_container::BlockContainer,
+    color_type_conversion::{rgba_to_color_string, str_to_rgba_option},
+    file_import_export::WhichFileDialog,
+    project_format::{
+        PROJECT_FILE_EXTENSION, ProjectFileMessage, ProjectSections, Table, parse_project,
+        parse_project_shapes, parse_project_shroud, project_header, table_bool, table_f32,
+        table_pos2, table_str, to_long_string, write_project_shroud,
+    },
+    reference_image::ImageLayer,
+    shape_container::ShapeContainer,
+    shroud_editor::{ShroudEditor, preferences::RecentFileKind},
+    shroud_interaction::ShroudInteraction,
+};
+
+impl ShroudEditor {
+    pub fn project_file_buttons(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let save_response = ui.button("Save Project");
+            if save_response.clicked() {
+                if let Some(path) = self.project_path_option.clone() {
+                    self.save_project_to_file(path);
+                } else {
+                    self.save_file_dialog(
+                        WhichFileDialog::ProjectSave,
+                        &format!("shroud.{PROJECT_FILE_EXTENSION}"),
+                    );
+                }
+            }
+            if ui.button("Save Project As").clicked() {
+                self.save_file_dialog(
+                    WhichFileDialog::ProjectSave,
+                    &format!("shroud.{PROJECT_FILE_EXTENSION}"),
+                );
+            }
+            let open_response = ui.button("Open Project");
+            if open_response.clicked() {
+                self.which_file_import = WhichFileDialog::ProjectOpen;
+                self.file_dialog.pick_file();
+            }
+            if let Some(message) = &self.project_file_message_option {
+                ui.label(message.to_string());
+            }
+            if !save_response.contains_pointer() && !open_response.contains_pointer() {
+                self.project_file_message_option = None;
+            }
+        });
+        if let Some(path) = &self.project_path_option {
+            ui.small(path.display().to_string());
+        }
+    }
+
+    pub fn save_project_to_file(&mut self, path: PathBuf) {
+        let project = self.serialise_project();
+        if File::create(&path)
+            .and_then(|mut file| file.write_all(project.as_bytes()))
+            .is_ok()
+        {
+            self.add_recent_file(RecentFileKind::Project, path.clone());
+            self.project_path_option = Some(path);
+            self.unsaved_changes = false;
+            self.remove_active_document_recovery_file();
+            self.project_file_message_option = Some(ProjectFileMessage::Saved);
+        } else {
+            self.project_file_message_option = Some(ProjectFileMessage::CouldNotWriteFile);
+        }
+    }
+
+    pub fn open_project_from_file(&mut self, ctx: &Context, path: PathBuf) {
+        let Ok(s) = read_to_string(&path) else {
+            self.project_file_message_option = Some(ProjectFileMessage::CouldNotOpenFile);
+            return;
+        };
+        match self.load_project(ctx, &s) {
+            Ok(()) => {
+                self.add_undo_history_logic();
+                self.unsaved_changes = false;
+                self.remove_active_document_recovery_file();
+                self.add_recent_file(RecentFileKind::Project, path.clone());
+                self.project_path_option = Some(path);
+                self.project_file_message_option = Some(ProjectFileMessage::Opened);
+            }
+            Err(err) => {
+                self.project_file_message_option = Some(err);
+            }
+        }
+    }
+
+    pub fn serialise_project(&self) -> String {
+        let mut s = project_header();
+        writeln!(
+            s,
+            "editor={{ grid_size={} grid_visible={} grid_snap_enabled={} angle_snap={} angle_snap_enabled={} }}",
+            self.settings_grid_size,
+            self.grid_visible,
+            self.grid_snap_enabled,
+            self.angle_snap,
+            self.angle_snap_enabled,
+        )
+        .unwrap();
+        writeln!(
+            s,
+            "block={{ shape={} scale={} fillColor={} fillColor1={} lineColor={} visible={} use_non_turreted_offset={} offset={{{},{}}} }}",
+            self.block_container.shape_id,
+            self.block_container.block.scale.unwrap_or(1),
+            rgba_to_color_string(self.block_container.color_1),
+            rgba_to_color_string(self.block_container.color_2),
+            rgba_to_color_string(self.block_container.line_color),
+            self.block_container.visible,
+            self.block_container.use_non_turreted_offset,
+            self.block_container.offset.x,
+            self.block_container.offset.y,
+        )
+        .unwrap();
+        writeln!(
+            s,
+            "reference_image={{ enabled={} above={} pos={{{},{}}} scale={} opacity={} }}",
+            self.reference_image.enabled,
+            self.reference_image.image_layer == ImageLayer::ImageAbove,
+            self.reference_image.pos.x,
+            self.reference_image.pos.y,
+            self.reference_image.scale,
+            self.reference_image.opacity,
+        )
+        .unwrap();
+        if let Some(path) = &self.reference_image.path_option {
+            writeln!(
+                s,
+                "reference_image_path={}",
+                to_long_string(&path.display().to_string())
+            )
+            .unwrap();
+        }
+        write_project_shroud(
+            &mut s,
+            &self.loaded_shapes_text,
+            &self.shroud,
+            &self.groups,
+            &self.shroud_trailing_comments,
+        );
+        s
+    }
+
+    pub fn load_project(&mut self, ctx: &Context, s: &str) -> Result<(), ProjectFileMessage> {
+        let sections = parse_project(s)?;
+        let (imported_shapes, mirror_pairs, non_mirrors) = parse_project_shapes(&sections)?;
+        let project_shapes = self.shapes_with_vanilla(imported_shapes.clone(), &non_mirrors);
+        let (shroud, trailing_comments) = parse_project_shroud(&sections, &project_shapes)?;
+        let block_container = self.project_block_container(&sections.block, &project_shapes)?;
+
+        self.load_shapes(imported_shapes, mirror_pairs, non_mirrors);
+        self.loaded_shapes_text = sections.shapes.to_string();
+        self.block_container = block_container;
+        self.icon_radius_option = None;
+        self.load_project_editor_settings(&sections.editor);
+        self.load_project_reference_image(ctx, &sections);
+
+        self.shroud = shroud;
+        self.shroud_trailing_comments = trailing_comments;
+        self.groups = sections.groups;
+        self.shroud_interaction = ShroudInteraction::none();
+        self.add_undo_history = true;
+        self.unlink_live_link_on_load(|_| false);
+        Ok(())
+    }
+
+    fn load_project_editor_settings(&mut self, editor: &Table) {
+        self.settings_grid_size = table_f32(editor, "grid_size").unwrap_or(self.settings_grid_size);
+        self.grid_size = self.settings_grid_size;
+        self.grid_visible = table_bool(editor, "grid_visible").unwrap_or(self.grid_visible);
+        self.grid_snap_enabled =
+            table_bool(editor, "grid_snap_enabled").unwrap_or(self.grid_snap_enabled);
+        self.angle_snap = table_f32(editor, "angle_snap").unwrap_or(self.angle_snap);
+        self.angle_snap_enabled =
+            table_bool(editor, "angle_snap_enabled").unwrap_or(self.angle_snap_enabled);
+    }
+
+    /// The current block settings with what the project's `block` table sets, using `shapes`.
+    fn project_block_container(
+        &self,
+        block: &Table,
+        shapes: &[ShapeContainer],
+    ) -> Result<BlockContainer, ProjectFileMessage> {
+        let mut block_container = self.block_container.clone();
+        if let Some(shape_id) = table_str(block, "shape") {
+            let shape = shapes
+                .iter()
+                .find(|shape| shape.s.get_id().unwrap().to_string() == shape_id)
+                .ok_or(ProjectFileMessage::BlockShape(shape_id.to_string()))?;
+            let scale = table_str(block, "scale")
+                .and_then(|scale| scale.parse::<usize>().ok())
+                .unwrap_or(1);
+            block_container.set_shape(shape, scale);
+        }
+        for (name, color_id) in [
+            ("fillColor", ShroudLayerColor::Color1),
+            ("fillColor1", ShroudLayerColor::Color2),
+            ("lineColor", ShroudLayerColor::LineColor),
+        ] {
+            if let Some(rgba) = table_str(block, name).and_then(str_to_rgba_option) {
+                block_container.set_color(color_id, rgba);
+            }
+        }
+        block_container.visible = table_bool(block, "visible").unwrap_or(block_container.visible);
+        block_container.use_non_turreted_offset = table_bool(block, "use_non_turreted_offset")
+            .unwrap_or(block_container.use_non_turreted_offset);
+        if block_container.use_non_turreted_offset {
+            block_container.update_non_turreted_offset();
+        } else if let Some(offset) = table_pos2(block, "offset") {
+            block_container.offset = offset;
+        }
+        Ok(block_container)
+    }
+
+    fn load_project_reference_image(&mut self, ctx: &Context, sections: &ProjectSections) {
+        let table = &sections.reference_image;
+        let reference_image = &mut self.reference_image;
+        reference_image.enabled = table_bool(table, "enabled").unwrap_or(reference_image.enabled);
+        reference_image.image_layer = if table_bool(table, "above").unwrap_or(false) {
+            ImageLayer::ImageAbove
+        } else {
+            ImageLayer::ImageBelow
+        };
+        reference_image.pos = table_pos2(table, "pos").unwrap_or(reference_image.pos);
+        reference_image.scale = table_f32(table, "scale").unwrap_or(reference_image.scale);
+        reference_image.opacity = table_f32(table, "opacity").unwrap_or(reference_image.opacity);
+        if let Some(path) = sections.reference_image_path_option {
+            self.import_reference_image_from_file(ctx, PathBuf::from(path));
+        }
+    }
+}
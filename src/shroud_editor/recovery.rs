@@ -0,0 +1,286 @@
+use std::{
+    fs::{File, OpenOptions, create_dir_all, read_dir, read_to_string, remove_file},
+    io,
+    mem::take,
+    panic,
+    path::{Path, PathBuf},
+    process,
+    sync::{Arc, Mutex},
+};
+
+use egui::{Context, Id, Modal};
+
+use crate::{
+    config::{config_dir, config_file_path, write_config_file},
+    project_format::ProjectFileMessage,
+    shroud_editor::ShroudEditor,
+};
+
+const RECOVERY_FILE_PREFIX: &str = "recovery-";
+const RECOVERY_FILE_EXTENSION: &str = ".shroudproj";
+const AUTOSAVE_INTERVAL: f64 = 30.0;
+
+#[derive(Default)]
+pub struct Recovery {
+    /// The latest project text of every tab with unsaved changes, if it has not been autosaved yet.
+    /// Shared with the panic hook.
+    unsaved_projects_option: Arc<Mutex<Option<Vec<Option<String>>>>>,
+    last_autosaved_projects: Vec<Option<String>>,
+    last_autosave_time: f64,
+    /// Held while the editor runs, so other editors leave its recovery files alone.
+    lock_file_option: Option<File>,
+    recovered_session_option: Option<RecoveredSession>,
+    message_option: Option<ProjectFileMessage>,
+}
+
+/// The recovery files of editors that did not close properly. Their locks are held until they are
+/// restored or discarded, so no other editor offers them too.
+#[derive(Default)]
+struct RecoveredSession {
+    projects: Vec<String>,
+    paths: Vec<PathBuf>,
+    lock_files: Vec<File>,
+}
+
+impl RecoveredSession {
+    fn remove_files(self) {
+        drop(self.lock_files);
+        self.paths.iter().for_each(|path| {
+            let _ = remove_file(path);
+        });
+    }
+}
+
+impl ShroudEditor {
+    /// Picks up the recovery files left by crashed editors, and makes a panic write out unsaved work before aborting.
+    pub fn start_recovery(&mut self) {
+        self.recovery.recovered_session_option = find_recovered_session();
+        if let Some(dir) = config_dir() {
+            let _ = create_dir_all(dir);
+        }
+        self.recovery.lock_file_option =
+            lock_file(&config_file_path(&lock_file_name(process::id())));
+        install_recovery_panic_hook(self.recovery.unsaved_projects_option.clone());
+    }
+
+    pub fn update_recovery_snapshot(&mut self) {
+        let projects = self.serialise_unsaved_documents();
+        if let Ok(mut unsaved_projects_option) = self.recovery.unsaved_projects_option.lock() {
+            *unsaved_projects_option = Some(projects);
+        }
+    }
+
+    pub fn autosave_logic(&mut self, ctx: &Context) {
+        if ctx.input(|i| i.viewport().close_requested()) {
+            remove_recovery_files(0);
+            self.recovery.lock_file_option = None;
+            let _ = remove_file(config_file_path(&lock_file_name(process::id())));
+            return;
+        }
+        if self.recovery.recovered_session_option.is_some() {
+            return;
+        }
+        let now = ctx.input(|i| i.time);
+        if now - self.recovery.last_autosave_time < AUTOSAVE_INTERVAL {
+            return;
+        }
+        self.recovery.last_autosave_time = now;
+        let projects = self.serialise_unsaved_documents();
+        if projects != self.recovery.last_autosaved_projects
+            && write_recovery_files(&projects).is_ok()
+        {
+            self.recovery.last_autosaved_projects = projects;
+            if let Ok(mut unsaved_projects_option) = self.recovery.unsaved_projects_option.lock() {
+                *unsaved_projects_option = None;
+            }
+        }
+    }
+
+    /// Called once the current tab is saved or opened from a file, so a crash does not offer back
+    /// work that is already in that file.
+    pub fn remove_active_document_recovery_file(&mut self) {
+        let _ = remove_file(config_file_path(&recovery_file_name(
+            self.active_document_idx,
+        )));
+        if let Some(project_option) = self
+            .recovery
+            .last_autosaved_projects
+            .get_mut(self.active_document_idx)
+        {
+            *project_option = None;
+        }
+        self.update_recovery_snapshot();
+    }
+
+    pub fn recovery_prompt(&mut self, ctx: &Context) {
+        let Some(recovered_session) = &self.recovery.recovered_session_option else {
+            return;
+        };
+        let tab_count = recovered_session.projects.len();
+        Modal::new(Id::new("recovery_prompt")).show(ctx, |ui| {
+            ui.heading("Recover Session");
+            ui.label(format!(
+                "The editor did not close properly last time. Restore the unsaved work in {tab_count} {}?",
+                if tab_count == 1 { "tab" } else { "tabs" }
+            ));
+            if let Some(message) = &self.recovery.message_option {
+                ui.label(message.to_string());
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Restore").clicked() {
+                    self.restore_recovered_session(ctx);
+                }
+                if ui.button("Discard").clicked()
+                    && let Some(recovered_session) = self.recovery.recovered_session_option.take()
+                {
+                    recovered_session.remove_files();
+                    self.recovery.message_option = None;
+                }
+            });
+        });
+    }
+
+    /// Restores the first recovered project into the current tab and the rest into new tabs. Any
+    /// that fail to load are kept for another try.
+    fn restore_recovered_session(&mut self, ctx: &Context) {
+        let Some(recovered_session) = &mut self.recovery.recovered_session_option else {
+            return;
+        };
+        let projects = take(&mut recovered_session.projects);
+        let mut failed_projects = Vec::new();
+        for (project_idx, project) in projects.into_iter().enumerate() {
+            if project_idx > 0 {
+                self.new_document();
+            }
+            match self.load_project(ctx, &project) {
+                Ok(()) => self.add_undo_history = true,
+                Err(err) => {
+                    if project_idx > 0 {
+                        self.close_document(self.active_document_idx);
+                    }
+                    failed_projects.push(project);
+                    self.recovery.message_option = Some(err);
+                }
+            }
+        }
+        if failed_projects.is_empty() {
+            if let Some(recovered_session) = self.recovery.recovered_session_option.take() {
+                recovered_session.remove_files();
+            }
+            self.recovery.message_option = None;
+        } else if let Some(recovered_session) = &mut self.recovery.recovered_session_option {
+            recovered_session.projects = failed_projects;
+        }
+    }
+}
+
+fn lock_file_name(pid: u32) -> String {
+    format!("{RECOVERY_FILE_PREFIX}{pid}.lock")
+}
+
+fn recovery_file_name(document_idx: usize) -> String {
+    format!(
+        "{RECOVERY_FILE_PREFIX}{}-{document_idx}{RECOVERY_FILE_EXTENSION}",
+        process::id()
+    )
+}
+
+/// The process ID and tab index in a recovery file's name. Files written before there were tabs
+/// have no index, and hold the only tab.
+fn parse_recovery_file_name(file_name: &str) -> Option<(u32, usize)> {
+    let name = file_name
+        .strip_prefix(RECOVERY_FILE_PREFIX)?
+        .strip_suffix(RECOVERY_FILE_EXTENSION)?;
+    let (pid, document_idx) = name.split_once('-').unwrap_or((name, "0"));
+    Some((pid.parse().ok()?, document_idx.parse().ok()?))
+}
+
+/// `None` if another editor already holds the lock.
+fn lock_file(path: &Path) -> Option<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .ok()?;
+    file.try_lock().ok()?;
+    Some(file)
+}
+
+/// Every recovery file whose editor is no longer running, in tab order.
+fn find_recovered_session() -> Option<RecoveredSession> {
+    let mut recovered_session = RecoveredSession::default();
+    let mut recovery_files = read_dir(config_dir().unwrap_or_else(|| PathBuf::from(".")))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let (pid, document_idx) = parse_recovery_file_name(entry.file_name().to_str()?)?;
+            Some((pid, document_idx, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    recovery_files.sort();
+    for session_files in recovery_files.chunk_by(|a, b| a.0 == b.0) {
+        let lock_path = config_file_path(&lock_file_name(session_files[0].0));
+        let Some(lock_file) = lock_file(&lock_path) else {
+            continue;
+        };
+        recovered_session.lock_files.push(lock_file);
+        recovered_session.paths.push(lock_path);
+        session_files.iter().for_each(|(_, _, path)| {
+            if let Ok(project) = read_to_string(path) {
+                recovered_session.projects.push(project);
+            }
+            recovered_session.paths.push(path.clone());
+        });
+    }
+    if recovered_session.projects.is_empty() {
+        None
+    } else {
+        Some(recovered_session)
+    }
+}
+
+/// Writes one file per tab with unsaved changes, and removes those of tabs that have since been
+/// saved or closed.
+fn write_recovery_files(projects: &[Option<String>]) -> io::Result<()> {
+    projects
+        .iter()
+        .enumerate()
+        .try_for_each(|(document_idx, project_option)| {
+            let file_name = recovery_file_name(document_idx);
+            match project_option {
+                Some(project) => write_config_file(&file_name, project),
+                None => {
+                    let _ = remove_file(config_file_path(&file_name));
+                    Ok(())
+                }
+            }
+        })?;
+    remove_recovery_files(projects.len());
+    Ok(())
+}
+
+/// Removes this editor's recovery files from tab `first_document_idx` onwards.
+fn remove_recovery_files(first_document_idx: usize) {
+    (first_document_idx..)
+        .take_while(|document_idx| {
+            remove_file(config_file_path(&recovery_file_name(*document_idx))).is_ok()
+        })
+        .for_each(drop);
+}
+
+/// The release profile aborts on panic, so this is the last chance to save anything.
+fn install_recovery_panic_hook(unsaved_projects_option: Arc<Mutex<Option<Vec<Option<String>>>>>) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+        if let Some(projects) = unsaved_projects_option
+            .try_lock()
+            .ok()
+            .and_then(|unsaved_projects_option| unsaved_projects_option.clone())
+        {
+            let _ = write_recovery_files(&projects);
+        }
+        default_hook(panic_info);
+    }));
+}
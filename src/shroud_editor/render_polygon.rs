@@ -80,6 +80,40 @@ pub fn polygon_line_logic(
     }
 }
 
+/// Crosses out a layer whose shape is not loaded, so the placeholder can't be mistaken for it.
+pub fn missing_shape_cross_logic(
+    render_outline_vertices_buffer: &mut Vec<RenderVertex>,
+    rect: Rect,
+    vertices: Vec<Pos2>,
+    offset: &DisplayOriented3D,
+    pan: Pos2,
+    zoom: f32,
+) {
+    let cross_color = Rgba::from_rgb(1.0, 0.0, 0.0);
+    let vertices: Vec<Pos2> = vertices
+        .iter()
+        .map(|vertex| Pos2::new(vertex.x + offset.x.to_f32(), vertex.y - offset.y.to_f32()))
+        .map(|vertex| world_pos_to_screen_pos(vertex, rect, pan, zoom))
+        .collect();
+    let half_len = vertices.len() / 2;
+    vertices
+        .iter()
+        .zip(vertices.iter().skip(half_len))
+        .take(half_len)
+        .for_each(|(vertex_a, vertex_b)| {
+            render_outline_vertices_buffer.push(RenderVertex::from_screen_data(
+                *vertex_a,
+                cross_color,
+                rect,
+            ));
+            render_outline_vertices_buffer.push(RenderVertex::from_screen_data(
+                *vertex_b,
+                cross_color,
+                rect,
+            ));
+        });
+}
+
 pub fn polygon_fill_logic(
     render_fill_vertices_buffer: &mut Vec<RenderVertex>,
     block_container: &BlockContainer,
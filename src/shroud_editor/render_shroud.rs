@@ -20,7 +20,7 @@ use crate::{
     shroud_editor::{
         ShroudEditor,
         grouping::render_group_outlines,
-        render_polygon::{polygon_fill_logic, polygon_line_logic},
+        render_polygon::{missing_shape_cross_logic, polygon_fill_logic, polygon_line_logic},
     },
     shroud_layer_container::ShroudLayerContainer,
     size_from_verts::{do2d_size_from_verts, do2d_square_size_from_verts},
@@ -341,6 +341,22 @@ fn render_shroud_body(
             }
         },
     );
+    shroud
+        .iter()
+        .filter(|shroud_layer_container| shroud_layer_container.shape_is_missing)
+        .for_each(|shroud_layer_container| {
+            missing_shape_cross_logic(
+                &mut render_outline_vertices_buffer,
+                rect,
+                shroud_layer_container.get_shroud_layer_vertices(),
+                shroud_layer_container.shroud_layer.offset.as_ref().unwrap(),
+                pan,
+                zoom,
+            );
+        });
+    if !render_outline_vertices_buffer.is_empty() {
+        render_lines(&render_outline_vertices_buffer, gl);
+    }
 }
 
 pub fn render_lines(render_outline_vertices: &[RenderVertex], gl: &Arc<eframe::glow::Context>) {
@@ -373,7 +389,9 @@ fn render_fills(render_fill_vertices: &[RenderVertex], gl: &Arc<eframe::glow::Co
     };
 }
 
-fn get_block_as_shroud_layer_container(block_container: &BlockContainer) -> ShroudLayerContainer {
+pub(super) fn get_block_as_shroud_layer_container(
+    block_container: &BlockContainer,
+) -> ShroudLayerContainer {
     ShroudLayerContainer {
         shroud_layer: if block_container.shape_id == "SQUARE" {
             ShroudLayer {
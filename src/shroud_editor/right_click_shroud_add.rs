@@ -11,9 +11,10 @@ use crate::{
     invert_y::invert_y_of_pos2,
     pos_and_display_oriented_number_conversion::{pos2_to_do2d, pos2_to_do3d},
     restructure_vertices::restructure_vertices,
-    shroud_editor::{ShroudEditor, tools::get_scaled_default_proportion_size},
+    shroud_editor::ShroudEditor,
     shroud_interaction::{MovingShroudLayerInteraction, MovingShroudSelection, ShroudInteraction},
     shroud_layer_container::ShroudLayerContainer,
+    transform::get_scaled_default_proportion_size,
 };
 
 impl ShroudEditor {
@@ -114,6 +115,9 @@ impl ShroudEditor {
                         mirror_index_option: None,
                         group_idx_option: None,
                         invert_height_of_mirror: selectable_shape.invert_height_of_mirror,
+                        comments: Vec::new(),
+                        unknown_fields: Vec::new(),
+                        shape_is_missing: false,
                     };
                     let idx = self.shroud.len();
                     self.shroud.push(shroud);
@@ -25,7 +25,21 @@ pub fn shroud_layer_moving(
     } else {
         *drag_pos
     };
-    selection.0.iter_mut().for_each(
+    move_selection_to(*potentially_snapped_drag_pos, selection, shroud);
+    // if response.drag_stopped() {
+    //     *shroud_interaction = ShroudInteraction::Inaction {
+    //         selection: shroud_interaction.selection(),
+    //     };
+    // }
+}
+
+/// Moves each selected layer, and its mirror, to `potentially_snapped_drag_pos` less where it was grabbed.
+pub fn move_selection_to(
+    potentially_snapped_drag_pos: Pos2,
+    selection: &MovingShroudSelection,
+    shroud: &mut [ShroudLayerContainer],
+) {
+    selection.0.iter().for_each(
         |MovingShroudLayerInteraction {
              idx: selected_index,
              relative_pos,
@@ -38,7 +52,7 @@ pub fn shroud_layer_moving(
                 .clone()
                 .unwrap();
             shroud[*selected_index].shroud_layer.offset = Some(pos2_to_do3d(
-                &(*potentially_snapped_drag_pos - *relative_pos),
+                &(potentially_snapped_drag_pos - *relative_pos),
                 old_offset.z.to_f32(),
             ));
             // if grid_snap_enabled {
@@ -57,9 +71,4 @@ pub fn shroud_layer_moving(
             }
         },
     );
-    // if response.drag_stopped() {
-    //     *shroud_interaction = ShroudInteraction::Inaction {
-    //         selection: shroud_interaction.selection(),
-    //     };
-    // }
 }
@@ -1,16 +1,15 @@
-use egui::{Color32, DragValue, Grid, Stroke, Ui};
+use egui::{Color32, DragValue, Grid, Label, Stroke, Ui};
 use egui_knob::{Knob, KnobStyle};
 use luexks_reassembly::blocks::shroud_layer::{ShroudLayer, ShroudLayerColor};
 
 use crate::{
+    add_mirror::{add_mirror, get_mirrored_shape_data},
     restructure_vertices::restructure_vertices,
     right_tri_angle_edge_case::{RIGHT_TRI, rotate_right_tri_shroud_layer_mirror},
     rotation_edgecase::{RotationEdgecase, rotation_edgecase_logic_degrees},
     shape_container::ShapeContainer,
     shroud_editor::{
-        DRAG_VALUE_MAX, DRAG_VALUE_MIN, ShroudEditor,
-        add_mirror::{add_mirror, get_mirrored_shape_data},
-        shape_combo_box::shroud_layer_shape_combo_box,
+        DRAG_VALUE_MAX, DRAG_VALUE_MIN, ShroudEditor, shape_combo_box::shroud_layer_shape_combo_box,
     },
     shroud_interaction::ShroudInteraction,
     shroud_layer_container::ShroudLayerContainer,
@@ -65,6 +64,7 @@ impl ShroudLayerSettingsTarget for SingleSettingsTarget<'_> {
         loaded_shapes_mirror_pairs: &[(usize, usize)],
     ) {
         self.shroud[self.idx].vertices = restructure_vertices(shape.s.get_first_scale_vertices());
+        self.shroud[self.idx].shape_is_missing = false;
         self.shroud[self.idx].invert_height_of_mirror = shape.invert_height_of_mirror;
         self.shroud[self.idx].shroud_layer.shape = shape.s.get_id();
         if let Some(mirror_idx) = self.shroud[self.idx].mirror_index_option {
@@ -466,6 +466,17 @@ pub fn taper_setting(
     });
 }
 
+/// Fields the editor does not understand can't be edited, only kept for export.
+fn unknown_fields_display(ui: &mut Ui, unknown_fields: &[(String, String)]) {
+    unknown_fields.iter().for_each(|(name, value)| {
+        ui.horizontal(|ui| {
+            ui.label(format!("{name}="));
+            ui.add_enabled(false, Label::new(value))
+                .on_disabled_hover_text("Not understood by the editor, but kept on export");
+        });
+    });
+}
+
 pub fn full_angle_settings(
     ui: &mut Ui,
     shroud_layer_settings_target: &mut impl ShroudLayerSettingsTarget,
@@ -580,6 +591,7 @@ impl ShroudEditor {
         if self.shroud[idx].group_idx_option.is_some() {
             shroud_layer_settings_height += 20.0;
         }
+        shroud_layer_settings_height += 20.0 * self.shroud[idx].unknown_fields.len() as f32;
         let window_bottom_y = ui.clip_rect().max.y;
         let top_of_shroud_layer_settings_y = ui.cursor().min.y;
         let shroud_layer_settings_are_off_screen =
@@ -635,6 +647,7 @@ impl ShroudEditor {
         if show_taper_setting {
             taper_setting(ui, shroud_layer_settings_target, add_undo_history);
         }
+        unknown_fields_display(ui, &self.shroud[idx].unknown_fields);
         // let end_y = ui.cursor().min.y;
         // println!("Height: {}", end_y - start_y);
     }
@@ -684,7 +697,6 @@ impl ShroudEditor {
                 add_mirror(
                     &mut self.shroud,
                     index,
-                    false,
                     &self.loaded_shapes,
                     &self.loaded_shapes_mirror_pairs,
                 );
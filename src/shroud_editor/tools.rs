@@ -1,17 +1,19 @@
 use crate::{
-    pos_and_display_oriented_number_conversion::{do3d_to_pos2, pos2_to_do2d},
+    pos_and_display_oriented_number_conversion::pos2_to_do2d,
     shroud_editor::{
         DRAG_VALUE_MAX, DRAG_VALUE_MIN, ShroudEditor,
         shroud_settings::{ShroudLayerSettingsTarget, SingleSettingsTarget, angle_knob_settings},
     },
     shroud_interaction::ShroudInteraction,
+    transform::{
+        get_scaled_default_proportion_size, move_shroud_layers, radial_shroud_layers,
+        scale_shroud_layers, scale_shroud_layers_size,
+    },
 };
 use egui::{DragValue, Ui, collapsing_header::CollapsingState, pos2};
-use itertools::Itertools;
 use luexks_reassembly::{
-    blocks::shroud_layer::ShroudLayer,
-    shapes::shape_id::ShapeId,
-    utility::display_oriented_math::{do2d_float_from, do3d_float_from},
+    blocks::shroud_layer::ShroudLayer, shapes::shape_id::ShapeId,
+    utility::display_oriented_math::do2d_float_from,
 };
 
 pub struct ToolSettings {
@@ -68,6 +70,50 @@ impl Default for ToolSettings {
     }
 }
 
+impl ToolSettings {
+    /// The values that are kept between sessions, which is everything but the bulk set layer.
+    pub fn preference_values_mut(&mut self) -> [(&'static str, &mut f32); 17] {
+        [
+            (
+                "move_selection_by_distance",
+                &mut self.move_selection_by_distance,
+            ),
+            ("move_selection_by_angle", &mut self.move_selection_by_angle),
+            ("move_selection_by_x", &mut self.move_selection_by_x),
+            ("move_selection_by_y", &mut self.move_selection_by_y),
+            ("move_selection_by_z", &mut self.move_selection_by_z),
+            (
+                "scale_by_no_offset_scale_factor",
+                &mut self.scale_by_no_offset_scale_factor,
+            ),
+            ("scale_by_scale_factor", &mut self.scale_by_scale_factor),
+            ("scale_by_about_x", &mut self.scale_by_about_x),
+            ("scale_by_about_y", &mut self.scale_by_about_y),
+            (
+                "scale_by_2_x_scale_factor",
+                &mut self.scale_by_2_x_scale_factor,
+            ),
+            (
+                "scale_by_2_y_scale_factor",
+                &mut self.scale_by_2_y_scale_factor,
+            ),
+            ("scale_by_2_about_x", &mut self.scale_by_2_about_x),
+            ("scale_by_2_about_y", &mut self.scale_by_2_about_y),
+            ("radial_about_x", &mut self.radial_about_x),
+            ("radial_about_y", &mut self.radial_about_y),
+            ("radial_by_angle", &mut self.radial_by_angle),
+            (
+                "default_proportions_scale",
+                &mut self.default_proportions_scale,
+            ),
+        ]
+    }
+
+    pub fn radial_by_count_mut(&mut self) -> &mut usize {
+        &mut self.radial_by_count
+    }
+}
+
 impl ShroudEditor {
     pub fn tools(&mut self, ui: &mut Ui) {
         CollapsingState::load_with_default_open(ui.ctx(), "tools".into(), false)
@@ -170,53 +216,15 @@ impl ShroudEditor {
             return;
         }
         self.add_undo_history = true;
-        let new_selection_len = count * selection.len();
-        let centre = pos2(about_x, about_y);
-        let angle_increment = 360.0 / count as f32;
-        let originals = selection
-            .iter()
-            .map(|shroud_layer_index| self.shroud[*shroud_layer_index].clone())
-            .collect::<Vec<_>>();
-        self.shroud.reserve(new_selection_len);
-        originals.into_iter().for_each(|mut original| {
-            if let Some(mirror_index) = original.mirror_index_option
-                && !selection.contains(&mirror_index)
-            {
-                self.shroud[mirror_index].mirror_index_option = None;
-            }
-            original.mirror_index_option = None;
-            original.group_idx_option = None;
-            (0..count).for_each(|i| {
-                let old_offset = original.shroud_layer.offset.as_ref().unwrap();
-                let relative_offset = do3d_to_pos2(old_offset) - centre;
-                let radial_angle = angle_increment * i as f32 + angle;
-                let (sin, cos) = radial_angle.to_radians().sin_cos();
-                let new_offset = do3d_float_from(
-                    centre.x + relative_offset.x * cos - relative_offset.y * sin,
-                    centre.y + relative_offset.x * sin + relative_offset.y * cos,
-                    old_offset.z.to_f32(),
-                );
-                let mut radial_shroud_layer_container = original.clone();
-                radial_shroud_layer_container.shroud_layer.offset = Some(new_offset);
-                *radial_shroud_layer_container
-                    .shroud_layer
-                    .angle
-                    .as_mut()
-                    .unwrap()
-                    .get_value_mut() += radial_angle;
-                self.shroud.push(radial_shroud_layer_container);
-            });
-        });
-        let sorted_selection = selection.into_iter().sorted().collect::<Vec<_>>();
-        sorted_selection.iter().rev().for_each(|i| {
-            self.shroud.remove(*i);
-            self.mirror_idx_logic_for_deleted_layer_idx(*i);
-            self.groups_logic_for_deleted_layer_idx(*i);
-        });
-        self.cull_groups();
-        self.shroud_interaction = ShroudInteraction::Inaction {
-            selection: (self.shroud.len() - new_selection_len..self.shroud.len()).collect(),
-        };
+        let selection = radial_shroud_layers(
+            &mut self.shroud,
+            &mut self.groups,
+            &selection,
+            pos2(about_x, about_y),
+            count,
+            angle,
+        );
+        self.shroud_interaction = ShroudInteraction::Inaction { selection };
     }
 
     fn move_tool(&mut self, ui: &mut Ui) {
@@ -225,37 +233,14 @@ impl ShroudEditor {
             let angle = self.tool_settings.move_selection_by_angle;
             if ui.button("Move by").clicked() {
                 self.add_undo_history = true;
-                self.shroud_interaction
-                    .selection()
-                    .iter()
-                    .for_each(|shroud_layer_index| {
-                        let offset = self.shroud[*shroud_layer_index]
-                            .shroud_layer
-                            .offset
-                            .as_ref()
-                            .unwrap();
-                        let new_offset = do3d_float_from(
-                            offset.x.to_f32() + *distance * angle.to_radians().cos(),
-                            offset.y.to_f32() + *distance * angle.to_radians().sin(),
-                            offset.z.to_f32(),
-                        );
-                        self.shroud[*shroud_layer_index].shroud_layer.offset = Some(new_offset);
-                        if let Some(mirror_index) =
-                            self.shroud[*shroud_layer_index].mirror_index_option
-                        {
-                            let offset = self.shroud[*shroud_layer_index]
-                                .shroud_layer
-                                .offset
-                                .as_ref()
-                                .unwrap();
-                            let new_mirror_offset = do3d_float_from(
-                                offset.x.to_f32(),
-                                -offset.y.to_f32(),
-                                offset.z.to_f32(),
-                            );
-                            self.shroud[mirror_index].shroud_layer.offset = Some(new_mirror_offset);
-                        }
-                    });
+                let (sin, cos) = angle.to_radians().sin_cos();
+                move_shroud_layers(
+                    &mut self.shroud,
+                    &self.shroud_interaction.selection(),
+                    *distance * cos,
+                    *distance * sin,
+                    0.0,
+                );
             }
             let angle = &mut self.tool_settings.move_selection_by_angle;
             ui.add(DragValue::new(distance).range(DRAG_VALUE_MIN..=DRAG_VALUE_MAX));
@@ -282,37 +267,13 @@ impl ShroudEditor {
             let z = &mut self.tool_settings.move_selection_by_z;
             if ui.button("Move by").clicked() {
                 self.add_undo_history = true;
-                self.shroud_interaction
-                    .selection()
-                    .iter()
-                    .for_each(|shroud_layer_index| {
-                        let offset = self.shroud[*shroud_layer_index]
-                            .shroud_layer
-                            .offset
-                            .as_ref()
-                            .unwrap();
-                        let new_offset = do3d_float_from(
-                            offset.x.to_f32() + *x,
-                            offset.y.to_f32() + *y,
-                            offset.z.to_f32() + *z,
-                        );
-                        self.shroud[*shroud_layer_index].shroud_layer.offset = Some(new_offset);
-                        if let Some(mirror_index) =
-                            self.shroud[*shroud_layer_index].mirror_index_option
-                        {
-                            let offset = self.shroud[*shroud_layer_index]
-                                .shroud_layer
-                                .offset
-                                .as_ref()
-                                .unwrap();
-                            let new_mirror_offset = do3d_float_from(
-                                offset.x.to_f32(),
-                                -offset.y.to_f32(),
-                                offset.z.to_f32(),
-                            );
-                            self.shroud[mirror_index].shroud_layer.offset = Some(new_mirror_offset);
-                        }
-                    });
+                move_shroud_layers(
+                    &mut self.shroud,
+                    &self.shroud_interaction.selection(),
+                    *x,
+                    *y,
+                    *z,
+                );
             }
             ui.label("X:");
             ui.add(
@@ -341,27 +302,11 @@ impl ShroudEditor {
             let scale_factor = &mut self.tool_settings.scale_by_no_offset_scale_factor;
             if ui.button("Scale by (no offset)").clicked() {
                 self.add_undo_history = true;
-                self.shroud_interaction
-                    .selection()
-                    .iter()
-                    .for_each(|shroud_layer_index| {
-                        let size = self.shroud[*shroud_layer_index]
-                            .shroud_layer
-                            .size
-                            .as_ref()
-                            .unwrap();
-                        let new_size = do2d_float_from(
-                            size.x.to_f32() * *scale_factor,
-                            size.y.to_f32() * *scale_factor,
-                        );
-                        self.shroud[*shroud_layer_index].shroud_layer.size = Some(new_size);
-                        if let Some(mirror_index) =
-                            self.shroud[*shroud_layer_index].mirror_index_option
-                        {
-                            self.shroud[mirror_index].shroud_layer.size =
-                                self.shroud[*shroud_layer_index].shroud_layer.size.clone();
-                        }
-                    });
+                scale_shroud_layers_size(
+                    &mut self.shroud,
+                    &self.shroud_interaction.selection(),
+                    *scale_factor,
+                );
             }
             ui.label("scale factor:");
             ui.add(
@@ -380,49 +325,13 @@ impl ShroudEditor {
             let about_y = &mut self.tool_settings.scale_by_about_y;
             if ui.button("Scale by").clicked() {
                 self.add_undo_history = true;
-                self.shroud_interaction
-                    .selection()
-                    .iter()
-                    .for_each(|shroud_layer_index| {
-                        let offset = self.shroud[*shroud_layer_index]
-                            .shroud_layer
-                            .offset
-                            .as_ref()
-                            .unwrap();
-                        let new_offset = do3d_float_from(
-                            *scale_factor * (offset.x.to_f32() - *about_x) + *about_x,
-                            *scale_factor * (offset.y.to_f32() - *about_y) + *about_y,
-                            offset.z.to_f32(),
-                        );
-                        self.shroud[*shroud_layer_index].shroud_layer.offset = Some(new_offset);
-                        let size = self.shroud[*shroud_layer_index]
-                            .shroud_layer
-                            .size
-                            .as_ref()
-                            .unwrap();
-                        let new_size = do2d_float_from(
-                            size.x.to_f32() * *scale_factor,
-                            size.y.to_f32() * *scale_factor,
-                        );
-                        self.shroud[*shroud_layer_index].shroud_layer.size = Some(new_size);
-                        if let Some(mirror_index) =
-                            self.shroud[*shroud_layer_index].mirror_index_option
-                        {
-                            let offset = self.shroud[*shroud_layer_index]
-                                .shroud_layer
-                                .offset
-                                .as_ref()
-                                .unwrap();
-                            let new_mirror_offset = do3d_float_from(
-                                offset.x.to_f32(),
-                                -offset.y.to_f32(),
-                                offset.z.to_f32(),
-                            );
-                            self.shroud[mirror_index].shroud_layer.offset = Some(new_mirror_offset);
-                            self.shroud[mirror_index].shroud_layer.size =
-                                self.shroud[*shroud_layer_index].shroud_layer.size.clone();
-                        }
-                    });
+                scale_shroud_layers(
+                    &mut self.shroud,
+                    &self.shroud_interaction.selection(),
+                    *scale_factor,
+                    *scale_factor,
+                    pos2(*about_x, *about_y),
+                );
             }
             ui.label("scale factor:");
             ui.add(
@@ -454,49 +363,13 @@ impl ShroudEditor {
         ui.horizontal(|ui| {
             if ui.button("Scale by").clicked() {
                 self.add_undo_history = true;
-                self.shroud_interaction
-                    .selection()
-                    .iter()
-                    .for_each(|shroud_layer_index| {
-                        let offset = self.shroud[*shroud_layer_index]
-                            .shroud_layer
-                            .offset
-                            .as_ref()
-                            .unwrap();
-                        let new_offset = do3d_float_from(
-                            *x_scale_factor * (offset.x.to_f32() - *about_x) + *about_x,
-                            *y_scale_factor * (offset.y.to_f32() - *about_y) + *about_y,
-                            offset.z.to_f32(),
-                        );
-                        self.shroud[*shroud_layer_index].shroud_layer.offset = Some(new_offset);
-                        let size = self.shroud[*shroud_layer_index]
-                            .shroud_layer
-                            .size
-                            .as_ref()
-                            .unwrap();
-                        let new_size = do2d_float_from(
-                            size.x.to_f32() * *x_scale_factor,
-                            size.y.to_f32() * *y_scale_factor,
-                        );
-                        self.shroud[*shroud_layer_index].shroud_layer.size = Some(new_size);
-                        if let Some(mirror_index) =
-                            self.shroud[*shroud_layer_index].mirror_index_option
-                        {
-                            let offset = self.shroud[*shroud_layer_index]
-                                .shroud_layer
-                                .offset
-                                .as_ref()
-                                .unwrap();
-                            let new_mirror_offset = do3d_float_from(
-                                offset.x.to_f32(),
-                                -offset.y.to_f32(),
-                                offset.z.to_f32(),
-                            );
-                            self.shroud[mirror_index].shroud_layer.offset = Some(new_mirror_offset);
-                            self.shroud[mirror_index].shroud_layer.size =
-                                self.shroud[*shroud_layer_index].shroud_layer.size.clone();
-                        }
-                    });
+                scale_shroud_layers(
+                    &mut self.shroud,
+                    &self.shroud_interaction.selection(),
+                    *x_scale_factor,
+                    *y_scale_factor,
+                    pos2(*about_x, *about_y),
+                );
             }
             ui.label("X scale factor:");
             ui.add(
@@ -527,25 +400,3 @@ impl ShroudEditor {
         });
     }
 }
-
-pub fn get_scaled_default_proportion_size(
-    verts: &[egui::Pos2],
-    default_proportions_scale: f32,
-) -> egui::Pos2 {
-    let (min_x, max_x, min_y, max_y) = verts.iter().fold(
-        (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
-        |(min_x, max_x, min_y, max_y), vert| {
-            (
-                vert.x.min(min_x),
-                vert.x.max(max_x),
-                vert.y.min(min_y),
-                vert.y.max(max_y),
-            )
-        },
-    );
-    let shape_size = (-min_x + max_x, -min_y + max_y);
-    pos2(
-        shape_size.0 * default_proportions_scale,
-        shape_size.1 * default_proportions_scale,
-    )
-}
@@ -33,6 +33,7 @@ impl ShroudEditor {
 
                 self.dragging_logic(ui);
                 self.placing_logic(ui);
+                self.object_snap_logic();
 
                 if self.reference_image.enabled
                     && matches!(self.reference_image.image_layer, ImageLayer::ImageBelow)
@@ -50,6 +51,8 @@ impl ShroudEditor {
 
                 self.selection_box_logic(ui, rect);
 
+                self.object_snap_indicator(ui, rect);
+
                 if self.reference_image.enabled
                     && matches!(self.reference_image.image_layer, ImageLayer::ImageAbove)
                 {
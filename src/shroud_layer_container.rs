@@ -1,6 +1,6 @@
 use core::f32;
 
-use egui::{Pos2, pos2};
+use emath::{Pos2, pos2};
 use luexks_reassembly::{
     blocks::shroud_layer::ShroudLayer,
     shapes::shape_id::ShapeId,
@@ -21,6 +21,12 @@ pub struct ShroudLayerContainer {
     pub mirror_index_option: Option<usize>,
     pub group_idx_option: Option<usize>,
     pub invert_height_of_mirror: bool,
+    /// `--` comment lines that came before this layer when it was imported, with their indentation.
+    pub comments: Vec<String>,
+    /// `key=value` pairs the editor does not understand, kept as written so they can be exported again.
+    pub unknown_fields: Vec<(String, String)>,
+    /// The shape is not loaded, so `vertices` are `MISSING_SHAPE_PLACEHOLDER` until it is.
+    pub shape_is_missing: bool,
 }
 
 impl Default for ShroudLayerContainer {
@@ -37,6 +43,9 @@ impl Default for ShroudLayerContainer {
             mirror_index_option: None,
             group_idx_option: None,
             invert_height_of_mirror: false,
+            comments: Vec::new(),
+            unknown_fields: Vec::new(),
+            shape_is_missing: false,
         }
     }
 }
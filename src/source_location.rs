@@ -0,0 +1,132 @@
+use std::{fmt::Display, ops::Range};
+
+const TOKEN_DISPLAY_MAX_CHARS: usize = 32;
+
+/// Where in a shroud or shapes text a parse failed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceLocation {
+    /// Byte range of the offending token.
+    pub range: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    /// The index of the shroud layer or shape the failure is inside of, if any.
+    pub entry_idx_option: Option<usize>,
+    entry_name: &'static str,
+}
+
+impl SourceLocation {
+    /// The parsers only ever hand back slices of the text they were given, so `at` is found by
+    /// where it points into `source`.
+    pub fn new(source: &str, at: &str, entry_name: &'static str) -> SourceLocation {
+        let source_start = source.as_ptr() as usize;
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(source_start)
+            .filter(|offset| *offset <= source.len())
+            .unwrap_or(0);
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit_once('\n')
+            .map_or(before, |(_, line_start)| line_start)
+            .chars()
+            .count()
+            + 1;
+        let rest = &source[offset..];
+        let token_len = match rest.find(|c: char| c.is_whitespace() || c == ',' || c == '}') {
+            Some(0) => rest.chars().next().map_or(0, char::len_utf8),
+            Some(token_len) => token_len,
+            None => rest.len(),
+        };
+        let token = rest[..token_len].to_string();
+        SourceLocation {
+            range: offset..offset + token_len,
+            line,
+            column,
+            token,
+            entry_idx_option: entry_idx(source, offset),
+            entry_name,
+        }
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(entry_idx) = self.entry_idx_option {
+            write!(f, "{} {}, ", self.entry_name, entry_idx)?;
+        }
+        write!(f, "line {}, column {}", self.line, self.column)?;
+        if !self.token.is_empty() {
+            let token = self
+                .token
+                .chars()
+                .take(TOKEN_DISPLAY_MAX_CHARS)
+                .collect::<String>();
+            write!(f, ", at `{token}`")?;
+        }
+        Ok(())
+    }
+}
+
+/// Shroud layers and shapes are both the tables directly inside the outermost table, so the
+/// entry is found by counting those up to `offset`.
+fn entry_idx(source: &str, offset: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut entry_count = 0usize;
+    let mut chars = source[..offset].char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '-' if source[idx..].starts_with("--") => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+            }
+            '{' => {
+                depth += 1;
+                if depth == 2 {
+                    entry_count += 1;
+                }
+            }
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    (entry_count > 0 && depth >= 2).then(|| entry_count - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_token_in_entry() {
+        let source = "shroud={\n\t{ offset={0,0,0} }\n\t{ tri_color_id=7 }\n}";
+        let at = &source[source.find('7').unwrap()..];
+        let location = SourceLocation::new(source, at, "shroud layer");
+        assert_eq!(location.line, 3);
+        assert_eq!(location.column, 17);
+        assert_eq!(location.token, "7");
+        assert_eq!(location.range, 45..46);
+        assert_eq!(location.entry_idx_option, Some(1));
+        assert_eq!(
+            location.to_string(),
+            "shroud layer 1, line 3, column 17, at `7`"
+        );
+    }
+
+    #[test]
+    fn braces_in_comments_are_not_entries() {
+        let source = "{\n\t-- {{\n\t{ verts=x }\n}";
+        let at = &source[source.find('x').unwrap()..];
+        let location = SourceLocation::new(source, at, "shape");
+        assert_eq!(location.entry_idx_option, Some(0));
+        assert_eq!((location.line, location.column), (3, 10));
+    }
+
+    #[test]
+    fn outside_of_any_entry() {
+        let source = "shroud={\n} junk";
+        let at = &source[source.find("junk").unwrap()..];
+        let location = SourceLocation::new(source, at, "shroud layer");
+        assert_eq!(location.entry_idx_option, None);
+        assert_eq!(location.to_string(), "line 2, column 3, at `junk`");
+    }
+}
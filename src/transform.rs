@@ -0,0 +1,153 @@
+use emath::{Pos2, pos2};
+use luexks_reassembly::utility::display_oriented_math::{do2d_float_from, do3d_float_from};
+
+use crate::{
+    layer_links::{Groups, remove_shroud_layers},
+    pos_and_display_oriented_number_conversion::do3d_to_pos2,
+    shroud_layer_container::ShroudLayerContainer,
+};
+
+pub fn move_shroud_layers(
+    shroud: &mut [ShroudLayerContainer],
+    layer_idxs: &[usize],
+    x: f32,
+    y: f32,
+    z: f32,
+) {
+    layer_idxs.iter().for_each(|layer_idx| {
+        let offset = shroud[*layer_idx].shroud_layer.offset.as_ref().unwrap();
+        let new_offset = do3d_float_from(
+            offset.x.to_f32() + x,
+            offset.y.to_f32() + y,
+            offset.z.to_f32() + z,
+        );
+        shroud[*layer_idx].shroud_layer.offset = Some(new_offset);
+        update_mirror_offset(shroud, *layer_idx);
+    });
+}
+
+/// Scales sizes only, leaving offsets where they are.
+pub fn scale_shroud_layers_size(
+    shroud: &mut [ShroudLayerContainer],
+    layer_idxs: &[usize],
+    scale_factor: f32,
+) {
+    layer_idxs.iter().for_each(|layer_idx| {
+        scale_shroud_layer_size(shroud, *layer_idx, scale_factor, scale_factor);
+    });
+}
+
+/// Scales sizes and offsets about `about`.
+pub fn scale_shroud_layers(
+    shroud: &mut [ShroudLayerContainer],
+    layer_idxs: &[usize],
+    x_scale_factor: f32,
+    y_scale_factor: f32,
+    about: Pos2,
+) {
+    layer_idxs.iter().for_each(|layer_idx| {
+        let offset = shroud[*layer_idx].shroud_layer.offset.as_ref().unwrap();
+        let new_offset = do3d_float_from(
+            x_scale_factor * (offset.x.to_f32() - about.x) + about.x,
+            y_scale_factor * (offset.y.to_f32() - about.y) + about.y,
+            offset.z.to_f32(),
+        );
+        shroud[*layer_idx].shroud_layer.offset = Some(new_offset);
+        update_mirror_offset(shroud, *layer_idx);
+        scale_shroud_layer_size(shroud, *layer_idx, x_scale_factor, y_scale_factor);
+    });
+}
+
+/// Replaces the layers at `layer_idxs` with `count` copies of each, rotated about `about` in even
+/// steps plus `angle` degrees. The copies are appended to the end of the shroud without any mirror
+/// or group links, and their indexes are returned.
+pub fn radial_shroud_layers(
+    shroud: &mut Vec<ShroudLayerContainer>,
+    groups: &mut Groups,
+    layer_idxs: &[usize],
+    about: Pos2,
+    count: usize,
+    angle: f32,
+) -> Vec<usize> {
+    let new_layers_len = count * layer_idxs.len();
+    let angle_increment = 360.0 / count as f32;
+    let originals = layer_idxs
+        .iter()
+        .map(|layer_idx| shroud[*layer_idx].clone())
+        .collect::<Vec<_>>();
+    shroud.reserve(new_layers_len);
+    originals.into_iter().for_each(|mut original| {
+        original.mirror_index_option = None;
+        original.group_idx_option = None;
+        (0..count).for_each(|i| {
+            let old_offset = original.shroud_layer.offset.as_ref().unwrap();
+            let relative_offset = do3d_to_pos2(old_offset) - about;
+            let radial_angle = angle_increment * i as f32 + angle;
+            let (sin, cos) = radial_angle.to_radians().sin_cos();
+            let new_offset = do3d_float_from(
+                about.x + relative_offset.x * cos - relative_offset.y * sin,
+                about.y + relative_offset.x * sin + relative_offset.y * cos,
+                old_offset.z.to_f32(),
+            );
+            let mut radial_shroud_layer_container = original.clone();
+            if i > 0 {
+                radial_shroud_layer_container.comments.clear();
+            }
+            radial_shroud_layer_container.shroud_layer.offset = Some(new_offset);
+            *radial_shroud_layer_container
+                .shroud_layer
+                .angle
+                .as_mut()
+                .unwrap()
+                .get_value_mut() += radial_angle;
+            shroud.push(radial_shroud_layer_container);
+        });
+    });
+    remove_shroud_layers(shroud, groups, layer_idxs);
+    (shroud.len() - new_layers_len..shroud.len()).collect()
+}
+
+pub fn get_scaled_default_proportion_size(verts: &[Pos2], default_proportions_scale: f32) -> Pos2 {
+    let (min_x, max_x, min_y, max_y) = verts.iter().fold(
+        (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+        |(min_x, max_x, min_y, max_y), vert| {
+            (
+                vert.x.min(min_x),
+                vert.x.max(max_x),
+                vert.y.min(min_y),
+                vert.y.max(max_y),
+            )
+        },
+    );
+    let shape_size = (-min_x + max_x, -min_y + max_y);
+    pos2(
+        shape_size.0 * default_proportions_scale,
+        shape_size.1 * default_proportions_scale,
+    )
+}
+
+fn scale_shroud_layer_size(
+    shroud: &mut [ShroudLayerContainer],
+    layer_idx: usize,
+    x_scale_factor: f32,
+    y_scale_factor: f32,
+) {
+    let size = shroud[layer_idx].shroud_layer.size.as_ref().unwrap();
+    let new_size = do2d_float_from(
+        size.x.to_f32() * x_scale_factor,
+        size.y.to_f32() * y_scale_factor,
+    );
+    shroud[layer_idx].shroud_layer.size = Some(new_size);
+    if let Some(mirror_index) = shroud[layer_idx].mirror_index_option {
+        shroud[mirror_index].shroud_layer.size = shroud[layer_idx].shroud_layer.size.clone();
+    }
+}
+
+fn update_mirror_offset(shroud: &mut [ShroudLayerContainer], layer_idx: usize) {
+    if let Some(mirror_index) = shroud[layer_idx].mirror_index_option {
+        let offset = shroud[layer_idx].shroud_layer.offset.as_ref().unwrap();
+        let new_mirror_offset =
+            do3d_float_from(offset.x.to_f32(), -offset.y.to_f32(), offset.z.to_f32());
+        shroud[mirror_index].shroud_layer.offset = Some(new_mirror_offset);
+    }
+}
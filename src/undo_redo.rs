@@ -23,6 +23,9 @@ impl ShroudEditor {
     fn load_undo_history_snapshot(&mut self, undo_history_snapshot: UndoHistorySnapshot) {
         self.shroud = undo_history_snapshot.shroud.clone();
         self.groups = undo_history_snapshot.groups.clone();
+        self.unsaved_changes = true;
+        self.update_recovery_snapshot();
+        self.live_link_logic();
     }
 
     pub fn add_undo_history_logic(&mut self) {
@@ -40,6 +43,9 @@ impl ShroudEditor {
             self.undo_history_index += 1;
         }
         self.undo_history.push(self.get_undo_history_snapshot());
+        self.unsaved_changes = true;
+        self.update_recovery_snapshot();
+        self.live_link_logic();
         // println!("\t\t{}\t{}", self.undo_history.len(), self.undo_history_index);
         // println!("{}\t{}", self.undo_history.len(), self.undo_history_index);
     }